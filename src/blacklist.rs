@@ -0,0 +1,85 @@
+use rusqlite::types::Value;
+
+use crate::query::Condition;
+
+// A blacklist entry is a tag or a space separated combination of tags that all have to be
+// present, "-tag" in a combination means the tag has to be absent, for example "swimsuit -beach"
+pub struct Blacklist {
+    entries: Vec<Vec<String>>,
+}
+
+impl Blacklist {
+    pub fn new(entries: &[String]) -> Self {
+        Blacklist {
+            entries: entries
+                .iter()
+                .map(|entry| {
+                    entry
+                        .split_whitespace()
+                        .map(|tag| tag.to_lowercase())
+                        .collect::<Vec<_>>()
+                })
+                .filter(|entry| !entry.is_empty())
+                .collect(),
+        }
+    }
+
+    // The same test as `matches` over the wallpapers table, false for an empty blacklist
+    pub fn sql(&self, params: &mut Vec<Value>) -> String {
+        if self.entries.is_empty() {
+            return "0".to_owned();
+        }
+        self.entries
+            .iter()
+            .map(|entry| {
+                let tags = entry
+                    .iter()
+                    .map(|tag| match tag.strip_prefix('-') {
+                        Some(tag) => {
+                            format!("NOT ({})", Condition::Tag(tag.to_owned()).sql(params))
+                        }
+                        None => format!("({})", Condition::Tag(tag.to_owned()).sql(params)),
+                    })
+                    .collect::<Vec<_>>()
+                    .join(" AND ");
+                format!("({tags})")
+            })
+            .collect::<Vec<_>>()
+            .join(" OR ")
+    }
+
+    pub fn matches(&self, tags: &[String]) -> bool {
+        self.entries.iter().any(|entry| {
+            entry.iter().all(|tag| match tag.strip_prefix('-') {
+                Some(tag) => !tags.iter().any(|t| t == tag),
+                None => tags.iter().any(|t| t == tag),
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tags(tags: &[&str]) -> Vec<String> {
+        tags.iter().map(|tag| tag.to_string()).collect()
+    }
+
+    #[test]
+    fn matches_entries() {
+        let blacklist = Blacklist::new(&tags(&["Gore", "swimsuit -beach", "  "]));
+        assert!(blacklist.matches(&tags(&["gore", "sky"])));
+        assert!(blacklist.matches(&tags(&["swimsuit", "pool"])));
+        assert!(!blacklist.matches(&tags(&["swimsuit", "beach"])));
+        assert!(!blacklist.matches(&tags(&["beach"])));
+        assert!(!blacklist.matches(&[]));
+    }
+
+    #[test]
+    fn empty_blacklist_matches_nothing() {
+        let blacklist = Blacklist::new(&tags(&[""]));
+        assert!(!blacklist.matches(&tags(&["gore"])));
+        assert_eq!(blacklist.sql(&mut vec![]), "0");
+    }
+}
@@ -4,13 +4,44 @@ use std::path::PathBuf;
 use std::str::FromStr;
 
 use crate::commands::Commands;
+use crate::query::{parse_ratio, RATIO_TOLERANCE};
 
 #[derive(Debug, Clone)]
 pub enum Range {
     LeftBounded(u16),
     RightBounded(u16),
     Exactly(u16),
+    Between(u16, u16),
 }
+impl Range {
+    // Sources can't search two way ranges, they get the lower bound and the rest is filtered after
+    // fetching
+    pub fn relaxed(&self) -> Range {
+        match self {
+            Range::Between(from, _) => Range::LeftBounded(*from),
+            range => range.clone(),
+        }
+    }
+
+    // The relaxed range as a search term, a two way range keeps its lower bound
+    pub fn comparison(&self) -> String {
+        match self {
+            Range::LeftBounded(num) | Range::Between(num, _) => format!(">={num}"),
+            Range::RightBounded(num) => format!("<={num}"),
+            Range::Exactly(num) => format!("{num}"),
+        }
+    }
+
+    pub fn contains(&self, value: i32) -> bool {
+        match self {
+            Range::LeftBounded(num) => value >= i32::from(*num),
+            Range::RightBounded(num) => value <= i32::from(*num),
+            Range::Exactly(num) => value == i32::from(*num),
+            Range::Between(from, to) => (i32::from(*from)..=i32::from(*to)).contains(&value),
+        }
+    }
+}
+
 impl From<u16> for Range {
     fn from(input: u16) -> Self {
         Range::Exactly(input)
@@ -31,19 +62,22 @@ impl FromStr for Range {
                     (Range::RightBounded(0), rest)
                 } else if let Some(rest) = input.strip_suffix("..") {
                     (Range::LeftBounded(0), rest)
+                } else if let Some((from, to)) = input.split_once("..") {
+                    let from: u16 = from.parse().map_err(RangeParseError::ParseIntError)?;
+                    let to: u16 = to.parse().map_err(RangeParseError::ParseIntError)?;
+                    if from > to {
+                        Err(RangeParseError::InvalidRange)?
+                    }
+                    return Ok(Range::Between(from, to));
                 } else {
                     (Range::Exactly(0), input)
                 };
 
-                if rest.contains("..") {
-                    Err(RangeParseError::TwoWayRangeNotSupported)?
-                }
-
                 match rest.parse::<u16>() {
                     Ok(number) => match variant {
-                        Range::Exactly(_) => Ok(Range::Exactly(number)),
                         Range::RightBounded(_) => Ok(Range::RightBounded(number)),
                         Range::LeftBounded(_) => Ok(Range::LeftBounded(number)),
+                        _ => Ok(Range::Exactly(number)),
                     },
                     Err(err) => Err(RangeParseError::ParseIntError(err)),
                 }
@@ -59,6 +93,7 @@ impl fmt::Display for Range {
             Range::LeftBounded(num) => write!(f, "{num}.."),
             Range::RightBounded(num) => write!(f, "..{num}"),
             Range::Exactly(num) => write!(f, "{num}"),
+            Range::Between(from, to) => write!(f, "{from}..{to}"),
         }
     }
 }
@@ -67,14 +102,90 @@ impl fmt::Display for Range {
 pub enum RangeParseError {
     #[error("konachan.net does not support full ranges")]
     FullRangeNotSupported,
-    #[error("konachan.net does not support two way ranges")]
-    TwoWayRangeNotSupported,
     #[error("Invalid range")]
     InvalidRange,
     #[error(transparent)]
     ParseIntError(core::num::ParseIntError),
 }
 
+#[derive(Debug, Clone)]
+pub struct RatioFilter {
+    pub ratio: f64,
+    pub tolerance: f64,
+}
+
+impl RatioFilter {
+    pub fn contains(&self, width: i32, height: i32) -> bool {
+        height > 0 && (f64::from(width) / f64::from(height) - self.ratio).abs() <= self.tolerance
+    }
+}
+
+impl FromStr for RatioFilter {
+    type Err = RatioParseError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let (ratio, tolerance) = match input.split_once('±').or_else(|| input.split_once("+-")) {
+            Some((ratio, tolerance)) => (
+                ratio,
+                tolerance
+                    .trim()
+                    .parse::<f64>()
+                    .ok()
+                    .filter(|tolerance| *tolerance >= 0.0)
+                    .ok_or(RatioParseError::InvalidTolerance)?,
+            ),
+            None => (input, RATIO_TOLERANCE),
+        };
+        let ratio = parse_ratio(ratio.trim()).ok_or(RatioParseError::InvalidRatio)?;
+        Ok(RatioFilter { ratio, tolerance })
+    }
+}
+
+impl fmt::Display for RatioFilter {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:.3}±{}", self.ratio, self.tolerance)
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum RatioParseError {
+    #[error("Invalid ratio, must be like 16:9, 16x9 or 1.78")]
+    InvalidRatio,
+    #[error("Invalid tolerance, must be a positive number like 0.05")]
+    InvalidTolerance,
+}
+
+pub fn parse_byte_size(input: &str) -> Result<u64, ByteSizeParseError> {
+    let input = input.trim();
+    let number_end = input
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(input.len());
+    let (number, unit) = input.split_at(number_end);
+    let number: u64 = number.parse().map_err(ByteSizeParseError::ParseIntError)?;
+
+    let multiplier: u64 = match unit.trim().to_uppercase().as_str() {
+        "" | "B" => 1,
+        "K" | "KB" | "KIB" => 1 << 10,
+        "M" | "MB" | "MIB" => 1 << 20,
+        "G" | "GB" | "GIB" => 1 << 30,
+        "T" | "TB" | "TIB" => 1 << 40,
+        _ => Err(ByteSizeParseError::InvalidUnit)?,
+    };
+    number
+        .checked_mul(multiplier)
+        .ok_or(ByteSizeParseError::Overflow)
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ByteSizeParseError {
+    #[error("Invalid unit, must be one of: B, K, M, G, T")]
+    InvalidUnit,
+    #[error("Size is too large")]
+    Overflow,
+    #[error(transparent)]
+    ParseIntError(core::num::ParseIntError),
+}
+
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 pub struct Cli {
@@ -87,6 +198,13 @@ pub struct Cli {
     #[arg(long, help = "Screen height")]
     pub screen_height: Option<u16>,
 
+    #[arg(
+        long,
+        global = true,
+        help = "Output (monitor) to use, defaults to the first one"
+    )]
+    pub output: Option<String>,
+
     #[arg(long)]
     pub cache_dir: Option<PathBuf>,
 
@@ -99,3 +217,96 @@ pub struct Cli {
     #[arg(long)]
     pub set_wallpaper_command: Option<String>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_ranges() {
+        assert!(matches!(Range::from_str("1920"), Ok(Range::Exactly(1920))));
+        assert!(matches!(
+            Range::from_str("1920.."),
+            Ok(Range::LeftBounded(1920))
+        ));
+        assert!(matches!(
+            Range::from_str("..1080"),
+            Ok(Range::RightBounded(1080))
+        ));
+        assert!(matches!(
+            Range::from_str("1920..2560"),
+            Ok(Range::Between(1920, 2560))
+        ));
+        assert!(matches!(
+            Range::from_str(".."),
+            Err(RangeParseError::FullRangeNotSupported)
+        ));
+        assert!(matches!(
+            Range::from_str("2560..1920"),
+            Err(RangeParseError::InvalidRange)
+        ));
+        assert!(matches!(
+            Range::from_str("1..2..3"),
+            Err(RangeParseError::InvalidRange)
+        ));
+        assert!(matches!(
+            Range::from_str("wide.."),
+            Err(RangeParseError::ParseIntError(_))
+        ));
+    }
+
+    #[test]
+    fn relaxes_two_way_ranges() {
+        let range = Range::Between(1920, 2560);
+        assert!(matches!(range.relaxed(), Range::LeftBounded(1920)));
+        assert_eq!(range.comparison(), ">=1920");
+        assert!(range.contains(2560));
+        assert!(!range.contains(3840));
+    }
+
+    #[test]
+    fn parses_ratio_filters() {
+        let filter = RatioFilter::from_str("16:9").unwrap();
+        assert_eq!(filter.tolerance, RATIO_TOLERANCE);
+        assert!(filter.contains(1920, 1080));
+        assert!(!filter.contains(1080, 1920));
+
+        let filter = RatioFilter::from_str("21:9±0.05").unwrap();
+        assert_eq!(filter.tolerance, 0.05);
+        assert!(filter.contains(2560, 1080));
+        assert!(!filter.contains(3440, 1440));
+
+        let filter = RatioFilter::from_str("1.5 +- 0").unwrap();
+        assert_eq!((filter.ratio, filter.tolerance), (1.5, 0.0));
+        assert!(!filter.contains(1, 0));
+
+        assert!(matches!(
+            RatioFilter::from_str("16:0"),
+            Err(RatioParseError::InvalidRatio)
+        ));
+        assert!(matches!(
+            RatioFilter::from_str("16:9±-1"),
+            Err(RatioParseError::InvalidTolerance)
+        ));
+    }
+
+    #[test]
+    fn parses_byte_sizes() {
+        assert_eq!(parse_byte_size("512").unwrap(), 512);
+        assert_eq!(parse_byte_size("2K").unwrap(), 2048);
+        assert_eq!(parse_byte_size("500 mb").unwrap(), 500 << 20);
+        assert_eq!(parse_byte_size("1GiB").unwrap(), 1 << 30);
+        assert!(matches!(
+            parse_byte_size("10 parsecs"),
+            Err(ByteSizeParseError::InvalidUnit)
+        ));
+        assert!(matches!(
+            parse_byte_size("20000000T"),
+            Err(ByteSizeParseError::Overflow)
+        ));
+        assert!(matches!(
+            parse_byte_size("M"),
+            Err(ByteSizeParseError::ParseIntError(_))
+        ));
+    }
+}
@@ -1,36 +1,83 @@
-use clap::{arg, command, Subcommand};
+use clap::{Args, Subcommand};
 
 use crate::{
-    cli::Range,
+    cli::{parse_byte_size, Range, RatioFilter},
+    order::Order,
+    query::Query,
     rating::{Category, KonachanRatingFilter, Rating},
+    source::SourceKind,
 };
 use core::str::FromStr;
+use list::{ListField, ListFormat, ListSort};
+use std::{num::NonZeroUsize, path::PathBuf};
 
+pub mod daemon;
 pub mod download;
+pub mod export;
+pub mod fix_extensions;
 pub mod get;
+pub mod import;
+pub mod list;
+pub mod migrate;
 pub mod review;
 pub mod set;
+pub mod verify;
+
+#[derive(Args, Debug)]
+pub struct DownloadFilters {
+    #[arg(long, value_parser = Range::from_str, help = "Width range like 1920.., ..3840, 2560..3840 or 2560")]
+    pub download_width: Option<Range>,
+
+    #[arg(long, value_parser = Range::from_str, help = "Height range like 1080.., ..2160, 1080..1600 or 1080")]
+    pub download_height: Option<Range>,
+
+    #[arg(long, value_parser = RatioFilter::from_str, help = "Aspect ratio like 21:9 or 16:9±0.05, the tolerance defaults to 0.1")]
+    pub ratio: Option<RatioFilter>,
+
+    #[arg(long)]
+    pub tags: Option<String>,
+
+    #[arg(long, value_parser = KonachanRatingFilter::from_str, default_value_t = KonachanRatingFilter::Safe)]
+    pub rating: KonachanRatingFilter,
+
+    #[arg(long, value_parser = Order::from_str, default_value_t = Order::ScoreDesc, help = "Order to download and review in: score-desc, score-asc, newest, oldest, random, random:<seed>, predicted, aspect-ratio or resolution")]
+    pub order: Order,
+}
 
 #[derive(Subcommand, Debug)]
 pub enum Commands {
     #[command(arg_required_else_help = false)]
     Download {
-        #[arg(long, value_parser = Range::from_str)]
-        download_width: Option<Range>,
+        #[arg(long, value_parser = SourceKind::from_str, default_value_t = SourceKind::Moebooru("konachan".to_owned()))]
+        source: SourceKind,
 
-        #[arg(long, value_parser = Range::from_str)]
-        download_height: Option<Range>,
+        #[command(flatten)]
+        filters: DownloadFilters,
 
-        #[arg(long)]
-        tags: Option<String>,
+        #[arg(long, help = "Number of wallpapers downloaded at once")]
+        jobs: Option<usize>,
 
-        #[arg(long, value_parser = KonachanRatingFilter::from_str, default_value_t = KonachanRatingFilter::Safe)]
-        rating: KonachanRatingFilter,
+        #[arg(long, help = "Only download, leave the wallpapers for a later review")]
+        no_review: bool,
+
+        #[arg(long, help = "Stop after downloading this many new wallpapers")]
+        limit: Option<NonZeroUsize>,
+
+        #[arg(long, value_parser = parse_byte_size, help = "Stop after downloading this many bytes, accepts K, M, G and T suffixes")]
+        max_bytes: Option<u64>,
     },
     #[command(arg_required_else_help = false)]
     Set {
         #[command(subcommand)]
         subcommand: SetSubcommand,
+
+        #[arg(
+            long,
+            global = true,
+            conflicts_with = "output",
+            help = "Set a wallpaper on every output, each picked on its own"
+        )]
+        all_outputs: bool,
     },
     Get {
         #[command(subcommand)]
@@ -39,6 +86,59 @@ pub enum Commands {
     Review {
         #[command(subcommand)]
         subcommand: ReviewSubcommand,
+
+        #[arg(long, global = true, value_parser = Order::from_str, help = "One of score-desc, score-asc, newest, oldest, random, random:<seed>, predicted, aspect-ratio, resolution")]
+        order: Option<Order>,
+    },
+    Import {
+        dir: PathBuf,
+
+        #[arg(long, help = "Import images from subdirectories too")]
+        recursive: bool,
+
+        #[arg(long, help = "Symlink images instead of copying them")]
+        link: bool,
+
+        #[arg(long, value_parser = Rating::from_str, default_value_t = Rating::Safe)]
+        rating: Rating,
+    },
+    Verify,
+    #[command(about = "Rename library images whose extension does not match their format")]
+    FixExtensions,
+    #[command(about = "Write the library back out as one json file per wallpaper")]
+    Export {
+        #[arg(help = "Directory to write to, defaults to the wallpapers directory")]
+        dir: Option<PathBuf>,
+    },
+    #[command(about = "Upgrade the library to the current format, backing it up first")]
+    Migrate,
+    #[command(about = "Keep running in the background, set, get and review go through it")]
+    Daemon {
+        #[arg(long, value_parser = clap::value_parser!(u64).range(1..), help = "Set a random wallpaper on every output this often, in minutes")]
+        rotate: Option<u64>,
+    },
+    #[command(
+        visible_alias = "search",
+        about = "Print the library wallpapers matching a query"
+    )]
+    List {
+        #[arg(value_parser = Query::from_str)]
+        query: Option<Query>,
+
+        #[arg(long, value_parser = ListFormat::from_str, default_value_t = ListFormat::Table)]
+        format: ListFormat,
+
+        #[arg(long, value_parser = ListSort::from_str, help = "Sort by score, size, added or shown, highest or most recent first")]
+        sort: Option<ListSort>,
+
+        #[arg(long)]
+        reverse: bool,
+
+        #[arg(long)]
+        limit: Option<usize>,
+
+        #[arg(long, value_parser = ListField::from_str, value_delimiter = ',', help = "Comma separated fields to print, json prints the whole wallpaper when empty")]
+        fields: Vec<ListField>,
     },
 }
 #[derive(Subcommand, Debug)]
@@ -47,6 +147,11 @@ pub enum ReviewSubcommand {
     Liked,
     Disliked,
     Borked,
+    Uncategorized,
+    Query {
+        #[arg(value_parser = Query::from_str)]
+        query: Query,
+    },
 }
 
 #[derive(Subcommand, Debug)]
@@ -65,6 +170,8 @@ pub enum SetSubcommand {
         rating: Rating,
         #[arg(long, value_parser = Category::from_str, default_value_t = Category::Any)]
         category: Category,
+        #[arg(long, value_parser = Query::from_str, help = "Only pick wallpapers matching this query, for example \"score:>100 ratio:16:9\"")]
+        query: Option<Query>,
     },
     File {
         path: String,
@@ -72,6 +179,13 @@ pub enum SetSubcommand {
     Md5 {
         md5: String,
     },
+    #[command(about = "Stretch one wallpaper across every output")]
+    Span {
+        #[arg(help = "Wallpaper to span, a random one if not given")]
+        md5: Option<String>,
+        #[arg(long, value_parser = Query::from_str, help = "Only pick wallpapers matching this query")]
+        query: Option<Query>,
+    },
 }
 
 #[derive(Subcommand, Debug)]
@@ -0,0 +1,229 @@
+use std::{
+    fs::Permissions,
+    iter,
+    os::unix::fs::PermissionsExt,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+
+use anyhow::{bail, Result};
+use clap::Parser;
+use tokio::{
+    fs,
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    net::{UnixListener, UnixStream},
+    select,
+    sync::Mutex,
+    time::{self, Instant},
+};
+use tokio_util::sync::CancellationToken;
+
+use super::{
+    get::get,
+    set::{set, targets},
+    Commands, SetSubcommand,
+};
+use crate::{
+    cli::Cli,
+    config::Config,
+    daemon::{socket_path, Message, Request, Response},
+    library::Library,
+    output::{select_output, Output},
+    rating::{Category, Rating},
+    wallpaper::set_wallpaper,
+};
+
+struct Daemon {
+    outputs: Vec<Output>,
+    library: Library,
+    // Canonical, to compare the libraries and configs clients ask for
+    wallpapers_dir: PathBuf,
+    config_dir: PathBuf,
+    // Read once, edits to the config take effect when the daemon is restarted
+    config: Config,
+    // One request at a time, so two clients don't race replacing the same setter
+    busy: Mutex<()>,
+}
+
+impl Daemon {
+    async fn run(&self, args: Vec<String>, cwd: &Path) -> Result<String> {
+        let args = Cli::try_parse_from(iter::once("aniwall".to_owned()).chain(args))?;
+        let output = select_output(
+            &self.outputs,
+            &args.output,
+            args.screen_width,
+            args.screen_height,
+        )?;
+
+        match args.command {
+            Commands::Set {
+                subcommand,
+                all_outputs,
+            } => {
+                // Relative to where the client was started, not the daemon
+                let subcommand = match subcommand {
+                    SetSubcommand::File { path } => SetSubcommand::File {
+                        path: cwd.join(path).to_string_lossy().into(),
+                    },
+                    subcommand => subcommand,
+                };
+                set(
+                    &subcommand,
+                    &targets(
+                        &subcommand,
+                        all_outputs,
+                        &self.outputs,
+                        output,
+                        args.screen_width,
+                        args.screen_height,
+                    )?,
+                    &self.library,
+                    &self.config,
+                    args.set_wallpaper_command,
+                )
+                .await?;
+                Ok(String::new())
+            }
+            Commands::Get { subcommand } => get(
+                &subcommand,
+                &self.library,
+                self.config_dir.clone(),
+                self.library.get_history(&output.name)?,
+            ),
+            _ => bail!("Only set and get are run by the daemon"),
+        }
+    }
+
+    async fn handle(&self, message: Message) -> Response {
+        if message.wallpapers_dir != self.wallpapers_dir || message.config_dir != self.config_dir {
+            return Response::OtherLibrary {
+                wallpapers_dir: self.wallpapers_dir.clone(),
+                config_dir: self.config_dir.clone(),
+            };
+        }
+
+        if let Request::Outputs = message.request {
+            return Response::Outputs {
+                outputs: self.outputs.clone(),
+            };
+        }
+
+        let _busy = self.busy.lock().await;
+        let result = match message.request {
+            Request::Outputs => unreachable!("answered without waiting"),
+            Request::Run { args, cwd } => self.run(args, &cwd).await,
+            Request::SetWallpaper {
+                path,
+                output,
+                targeted,
+                command,
+            } => set_wallpaper(&self.config, &path, &output, targeted, command)
+                .await
+                .map(|_| String::new()),
+        };
+        match result {
+            Ok(output) => Response::Ok { output },
+            Err(err) => Response::Error {
+                message: format!("{err:#}"),
+            },
+        }
+    }
+
+    async fn serve(&self, stream: UnixStream) -> Result<()> {
+        let (reader, mut writer) = stream.into_split();
+        let mut lines = BufReader::new(reader).lines();
+        while let Some(line) = lines.next_line().await? {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let response = match serde_json::from_str(&line) {
+                Ok(message) => self.handle(message).await,
+                Err(err) => Response::Error {
+                    message: format!("Invalid request: {err}"),
+                },
+            };
+            let mut line = serde_json::to_string(&response)?;
+            line.push('\n');
+            writer.write_all(line.as_bytes()).await?;
+        }
+        Ok(())
+    }
+
+    // Same as `set random --all-outputs`
+    async fn rotate(&self) -> Result<()> {
+        let _busy = self.busy.lock().await;
+        set(
+            &SetSubcommand::Random {
+                rating: Rating::Any,
+                category: Category::Any,
+                query: None,
+            },
+            &self.outputs,
+            &self.library,
+            &self.config,
+            None,
+        )
+        .await
+    }
+}
+
+pub async fn daemon(
+    outputs: Vec<Output>,
+    rotate: Option<u64>,
+    library: Library,
+    config: Config,
+    config_dir: PathBuf,
+    token: CancellationToken,
+) -> Result<()> {
+    let socket_path = socket_path()?;
+    if UnixStream::connect(&socket_path).await.is_ok() {
+        bail!(
+            "A daemon is already listening on {}",
+            socket_path.to_string_lossy()
+        );
+    }
+    // Left behind by a daemon that was killed
+    let _ = fs::remove_file(&socket_path).await;
+    let listener = UnixListener::bind(&socket_path)?;
+    fs::set_permissions(&socket_path, Permissions::from_mode(0o600)).await?;
+    eprintln!("Listening on {}", socket_path.to_string_lossy());
+
+    let daemon = Arc::new(Daemon {
+        outputs,
+        wallpapers_dir: library.dir().canonicalize()?,
+        library,
+        config_dir: config_dir.canonicalize()?,
+        config,
+        busy: Mutex::new(()),
+    });
+    let mut rotation = rotate.map(|minutes| {
+        let period = Duration::from_secs(minutes * 60);
+        time::interval_at(Instant::now() + period, period)
+    });
+
+    loop {
+        select! {
+            _ = token.cancelled() => break,
+            accepted = listener.accept() => match accepted {
+                Ok((stream, _)) => {
+                    let daemon = daemon.clone();
+                    tokio::spawn(async move {
+                        if let Err(err) = daemon.serve(stream).await {
+                            eprintln!("Client connection failed: {err:#}");
+                        }
+                    });
+                }
+                Err(err) => eprintln!("Could not accept a connection: {err}"),
+            },
+            _ = async { rotation.as_mut().unwrap().tick().await }, if rotation.is_some() => {
+                if let Err(err) = daemon.rotate().await {
+                    eprintln!("Could not rotate the wallpapers: {err:#}");
+                }
+            }
+        }
+    }
+
+    fs::remove_file(&socket_path).await?;
+    Ok(())
+}
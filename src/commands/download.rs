@@ -1,45 +1,65 @@
 use std::{path::PathBuf, str::FromStr};
 
+use super::DownloadFilters;
 use crate::{
-    cli::Range, download::download_wallpapers, process::process_wallpapers,
-    rating::KonachanRatingFilter, wallpaper_history::History, wallpaper_list::get_wallpaper_list,
+    blacklist::Blacklist,
+    cli::Range,
+    download::{download_wallpapers, DownloadLimits},
+    process::{process_wallpapers, ReviewSession},
+    source::{Post, SourceKind},
+    wallpaper_list::{get_wallpaper_list, Filters},
 };
 use anyhow::Result;
 use tokio::{select, sync::mpsc};
-use tokio_util::sync::CancellationToken;
 
 pub async fn download(
-    download_width: &Option<Range>,
-    download_height: &Option<Range>,
-    screen_width: u16,
-    screen_height: u16,
-    tags: &Option<String>,
-    rating: &KonachanRatingFilter,
-    wallpapers_dir: PathBuf,
-    config_dir: PathBuf,
+    source: &SourceKind,
+    filters: &DownloadFilters,
+    jobs: Option<usize>,
+    no_review: bool,
+    limits: DownloadLimits,
     cache_dir: PathBuf,
-    history: History,
-    token: CancellationToken,
-    set_wallpaper_command_override: Option<String>,
+    session: ReviewSession,
 ) -> Result<()> {
-    let wallpaper_list = get_wallpaper_list(
-        &cache_dir,
-        &download_width
+    let ReviewSession {
+        output,
+        library,
+        config,
+        ..
+    } = &session;
+    let source = source.source(config)?;
+    let jobs = jobs.unwrap_or(config.download_jobs).max(1);
+    let filters = Filters {
+        width: filters
+            .download_width
             .clone()
-            .unwrap_or(Range::from_str(&format!("{}..", screen_width))?),
-        &download_height
+            .unwrap_or(Range::from_str(&format!("{}..", output.width))?),
+        height: filters
+            .download_height
             .clone()
-            .unwrap_or(Range::from_str(&format!("{}..", screen_height))?),
-        tags,
-        rating,
-    )
-    .await?;
+            .unwrap_or(Range::from_str(&format!("{}..", output.height))?),
+        tags: filters.tags.clone(),
+        rating: filters.rating.clone(),
+        order: filters.order.clone(),
+        ratio: filters.ratio.clone(),
+    };
+    let blacklist = Blacklist::new(&config.blacklist);
+    let mut wallpaper_list: Vec<Post> = get_wallpaper_list(&cache_dir, source.as_ref(), &filters)
+        .await?
+        .into_iter()
+        .filter(|post| !blacklist.matches(&post.tags))
+        .collect();
+    filters.order.sort(
+        &mut wallpaper_list,
+        &filters.order.preferences(library)?,
+        output.ratio(),
+    );
 
-    let (downloaded_wallpapers_tx, downloaded_wallpapers_rx) = mpsc::channel(10);
+    let (downloaded_wallpapers_tx, mut downloaded_wallpapers_rx) = mpsc::channel(10);
 
     tokio::task::spawn({
-        let token = token.clone();
-        let wallpapers_dir = wallpapers_dir.clone();
+        let token = session.token.clone();
+        let library = library.clone();
 
         async move {
             select! {
@@ -47,8 +67,11 @@ pub async fn download(
                 _ = token.cancelled() => {}
                 _ = async move {
                         download_wallpapers(
+                            source.as_ref(),
                             wallpaper_list,
-                            &wallpapers_dir,
+                            &library,
+                            jobs,
+                            limits,
                             downloaded_wallpapers_tx).await
 
                 } => {}
@@ -56,16 +79,13 @@ pub async fn download(
         }
     });
 
-    process_wallpapers(
-        downloaded_wallpapers_rx,
-        token,
-        history,
-        wallpapers_dir,
-        config_dir,
-        screen_width,
-        screen_height,
-        set_wallpaper_command_override,
-    )
-    .await?;
+    if no_review {
+        while let Some(wallpaper) = downloaded_wallpapers_rx.recv().await {
+            println!("{}", wallpaper.md5);
+        }
+        return Ok(());
+    }
+
+    process_wallpapers(downloaded_wallpapers_rx, session).await?;
     Ok(())
 }
@@ -0,0 +1,14 @@
+use std::path::PathBuf;
+
+use crate::library::Library;
+use anyhow::Result;
+
+pub fn export(dir: &Option<PathBuf>, library: &Library) -> Result<()> {
+    let export_dir = dir.clone().unwrap_or_else(|| library.dir().to_path_buf());
+    let count = library.export(&export_dir)?;
+    eprintln!(
+        "Exported {count} wallpapers to {}",
+        export_dir.to_string_lossy()
+    );
+    Ok(())
+}
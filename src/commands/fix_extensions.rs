@@ -0,0 +1,56 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{image_format::ImageFormat, library::Library};
+use anyhow::Result;
+
+fn fix_extension(path: &Path) -> Result<Option<PathBuf>> {
+    let Some(format) = ImageFormat::from_path(path) else {
+        return Ok(None);
+    };
+    let labeled = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .and_then(ImageFormat::from_extension);
+    if labeled == Some(format) {
+        return Ok(None);
+    }
+
+    let fixed_path = path.with_extension(format.extension());
+    fs::rename(path, &fixed_path)?;
+    Ok(Some(fixed_path))
+}
+
+pub fn fix_extensions(library: &Library) -> Result<()> {
+    for mut wallpaper in library.wallpapers()? {
+        let mut changed = false;
+
+        if let Some(fixed_path) = fix_extension(&wallpaper.downloaded_image_path)? {
+            println!(
+                "{}\t{}",
+                wallpaper.downloaded_image_path.to_string_lossy(),
+                fixed_path.to_string_lossy()
+            );
+            wallpaper.downloaded_image_path = fixed_path;
+            changed = true;
+        }
+        if let Some(crop_data) = &mut wallpaper.crop_data {
+            if let Some(fixed_path) = fix_extension(&crop_data.cropped_image_path)? {
+                println!(
+                    "{}\t{}",
+                    crop_data.cropped_image_path.to_string_lossy(),
+                    fixed_path.to_string_lossy()
+                );
+                crop_data.cropped_image_path = fixed_path;
+                changed = true;
+            }
+        }
+
+        if changed {
+            library.save_wallpaper(&wallpaper)?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,72 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    crop::get_image_dimensions, image_format::ImageFormat, library::Library, rating::Rating,
+    wallpaper::Wallpaper,
+};
+use anyhow::Result;
+use glob::glob;
+
+fn import_wallpaper(
+    path: &Path,
+    format: ImageFormat,
+    library: &Library,
+    link: bool,
+    rating: &Rating,
+) -> Result<Option<Wallpaper>> {
+    let md5 = format!("{:x}", md5::compute(fs::read(path)?));
+    if library.contains(&md5)? {
+        return Ok(None);
+    }
+
+    let (width, height) = get_image_dimensions(path)?;
+    let downloaded_image_path = library.dir().join(&md5).with_extension(format.extension());
+
+    if !downloaded_image_path.exists() {
+        if link {
+            std::os::unix::fs::symlink(fs::canonicalize(path)?, &downloaded_image_path)?;
+        } else {
+            fs::copy(path, &downloaded_image_path)?;
+        }
+    }
+
+    let wallpaper = Wallpaper::from_local(
+        md5,
+        path,
+        width,
+        height,
+        rating.to_owned(),
+        downloaded_image_path,
+    );
+    library.save_wallpaper(&wallpaper)?;
+    Ok(Some(wallpaper))
+}
+
+pub fn import(
+    dir: &Path,
+    recursive: bool,
+    link: bool,
+    rating: &Rating,
+    library: &Library,
+) -> Result<()> {
+    let pattern = if recursive { "**/*" } else { "*" };
+    let paths: Vec<PathBuf> = glob(&format!("{}/{pattern}", dir.to_str().unwrap()))?
+        .filter_map(Result::ok)
+        .filter(|path| path.is_file())
+        .collect();
+
+    for path in paths {
+        let Some(format) = ImageFormat::from_path(&path) else {
+            continue;
+        };
+        match import_wallpaper(&path, format, library, link, rating) {
+            Ok(Some(wallpaper)) => println!("{}\t{}", wallpaper.md5, path.to_string_lossy()),
+            Ok(None) => eprintln!("Already imported: {}", path.to_string_lossy()),
+            Err(err) => eprintln!("Failed to import {}: {err}", path.to_string_lossy()),
+        }
+    }
+    Ok(())
+}
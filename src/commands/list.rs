@@ -0,0 +1,348 @@
+use std::{fmt, str::FromStr};
+
+use crate::{library::Library, query::Query, wallpaper::Wallpaper};
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde_json::{Map, Value};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ListFormat {
+    Table,
+    Json,
+    Ndjson,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ListFormatParseError {
+    VariantNotFound,
+}
+
+impl fmt::Display for ListFormatParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ListFormatParseError::VariantNotFound => write!(
+                f,
+                "Matching variant not found, must be one of: table, json, ndjson"
+            ),
+        }
+    }
+}
+
+impl FromStr for ListFormat {
+    type Err = ListFormatParseError;
+
+    fn from_str(input: &str) -> Result<ListFormat, Self::Err> {
+        match input {
+            "table" => Ok(ListFormat::Table),
+            "json" => Ok(ListFormat::Json),
+            "ndjson" | "jsonl" => Ok(ListFormat::Ndjson),
+            _ => Err(ListFormatParseError::VariantNotFound),
+        }
+    }
+}
+
+impl fmt::Display for ListFormat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ListFormat::Table => write!(f, "table"),
+            ListFormat::Json => write!(f, "json"),
+            ListFormat::Ndjson => write!(f, "ndjson"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ListSort {
+    Score,
+    Size,
+    Added,
+    Shown,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ListSortParseError {
+    VariantNotFound,
+}
+
+impl fmt::Display for ListSortParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ListSortParseError::VariantNotFound => write!(
+                f,
+                "Matching variant not found, must be one of: score, size, added, shown"
+            ),
+        }
+    }
+}
+
+impl FromStr for ListSort {
+    type Err = ListSortParseError;
+
+    fn from_str(input: &str) -> Result<ListSort, Self::Err> {
+        match input {
+            "score" => Ok(ListSort::Score),
+            "size" | "resolution" => Ok(ListSort::Size),
+            "added" | "date-added" => Ok(ListSort::Added),
+            "shown" | "last-shown" => Ok(ListSort::Shown),
+            _ => Err(ListSortParseError::VariantNotFound),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ListField {
+    Md5,
+    Source,
+    PostId,
+    Url,
+    Width,
+    Height,
+    Score,
+    Rating,
+    Category,
+    Prefered,
+    Path,
+    CroppedPath,
+    Tags,
+    Artist,
+    Uploader,
+    SourceUrl,
+    PostUrl,
+    Created,
+    Added,
+    Reviewed,
+    Shown,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ListFieldParseError {
+    VariantNotFound,
+}
+
+impl fmt::Display for ListFieldParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ListFieldParseError::VariantNotFound => write!(
+                f,
+                "Matching variant not found, must be one of: md5, source, post_id, url, width, height, score, rating, category, prefered, path, cropped_path, tags, artist, uploader, source_url, post_url, created, added, reviewed, shown"
+            ),
+        }
+    }
+}
+
+impl FromStr for ListField {
+    type Err = ListFieldParseError;
+
+    fn from_str(input: &str) -> Result<ListField, Self::Err> {
+        match input {
+            "md5" => Ok(ListField::Md5),
+            "source" => Ok(ListField::Source),
+            "post_id" => Ok(ListField::PostId),
+            "url" => Ok(ListField::Url),
+            "width" => Ok(ListField::Width),
+            "height" => Ok(ListField::Height),
+            "score" => Ok(ListField::Score),
+            "rating" => Ok(ListField::Rating),
+            "category" => Ok(ListField::Category),
+            "prefered" => Ok(ListField::Prefered),
+            "path" => Ok(ListField::Path),
+            "cropped_path" => Ok(ListField::CroppedPath),
+            "tags" => Ok(ListField::Tags),
+            "artist" => Ok(ListField::Artist),
+            "uploader" => Ok(ListField::Uploader),
+            "source_url" => Ok(ListField::SourceUrl),
+            "post_url" => Ok(ListField::PostUrl),
+            "created" => Ok(ListField::Created),
+            "added" => Ok(ListField::Added),
+            "reviewed" => Ok(ListField::Reviewed),
+            "shown" => Ok(ListField::Shown),
+            _ => Err(ListFieldParseError::VariantNotFound),
+        }
+    }
+}
+
+impl fmt::Display for ListField {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ListField::Md5 => write!(f, "md5"),
+            ListField::Source => write!(f, "source"),
+            ListField::PostId => write!(f, "post_id"),
+            ListField::Url => write!(f, "url"),
+            ListField::Width => write!(f, "width"),
+            ListField::Height => write!(f, "height"),
+            ListField::Score => write!(f, "score"),
+            ListField::Rating => write!(f, "rating"),
+            ListField::Category => write!(f, "category"),
+            ListField::Prefered => write!(f, "prefered"),
+            ListField::Path => write!(f, "path"),
+            ListField::CroppedPath => write!(f, "cropped_path"),
+            ListField::Tags => write!(f, "tags"),
+            ListField::Artist => write!(f, "artist"),
+            ListField::Uploader => write!(f, "uploader"),
+            ListField::SourceUrl => write!(f, "source_url"),
+            ListField::PostUrl => write!(f, "post_url"),
+            ListField::Created => write!(f, "created"),
+            ListField::Added => write!(f, "added"),
+            ListField::Reviewed => write!(f, "reviewed"),
+            ListField::Shown => write!(f, "shown"),
+        }
+    }
+}
+
+const DEFAULT_TABLE_FIELDS: [ListField; 7] = [
+    ListField::Md5,
+    ListField::Source,
+    ListField::Width,
+    ListField::Height,
+    ListField::Score,
+    ListField::Rating,
+    ListField::Category,
+];
+
+fn format_time(time: &Option<DateTime<Utc>>) -> Value {
+    time.map(|time| time.to_rfc3339()).into()
+}
+
+fn value(wallpaper: &Wallpaper, field: &ListField) -> Value {
+    match field {
+        ListField::Md5 => wallpaper.md5.clone().into(),
+        ListField::Source => wallpaper.source.clone().into(),
+        ListField::PostId => wallpaper.post_id.clone().into(),
+        ListField::Url => wallpaper.original_url.clone().into(),
+        ListField::Width => wallpaper.original_width.into(),
+        ListField::Height => wallpaper.original_height.into(),
+        ListField::Score => wallpaper.score.into(),
+        ListField::Rating => wallpaper.rating.to_string().into(),
+        ListField::Category => wallpaper
+            .category
+            .as_ref()
+            .map(|category| category.to_string())
+            .into(),
+        ListField::Prefered => serde_json::to_value(&wallpaper.prefered).unwrap_or_default(),
+        ListField::Path => wallpaper.downloaded_image_path.to_string_lossy().into(),
+        ListField::CroppedPath => wallpaper
+            .crop_data
+            .as_ref()
+            .map(|crop_data| crop_data.cropped_image_path.to_string_lossy().into_owned())
+            .into(),
+        ListField::Tags => wallpaper.tags.join(" ").into(),
+        ListField::Artist => wallpaper.artist.clone().into(),
+        ListField::Uploader => wallpaper.uploader.clone().into(),
+        ListField::SourceUrl => wallpaper.source_url.clone().into(),
+        ListField::PostUrl => wallpaper.post_url.clone().into(),
+        ListField::Created => format_time(&wallpaper.created_at),
+        ListField::Added => format_time(&wallpaper.added_at),
+        ListField::Reviewed => format_time(&wallpaper.reviewed_at),
+        ListField::Shown => format_time(&wallpaper.last_set_at),
+    }
+}
+
+fn object(wallpaper: &Wallpaper, fields: &[ListField]) -> Result<Value> {
+    if fields.is_empty() {
+        return Ok(serde_json::to_value(wallpaper)?);
+    }
+    let mut object = Map::new();
+    for field in fields {
+        object.insert(field.to_string(), value(wallpaper, field));
+    }
+    Ok(Value::Object(object))
+}
+
+fn sort_wallpapers(wallpapers: &mut [Wallpaper], sort: &ListSort) {
+    match sort {
+        ListSort::Score => wallpapers.sort_by_key(|wallpaper| wallpaper.score),
+        ListSort::Size => wallpapers.sort_by_key(|wallpaper| {
+            i64::from(wallpaper.original_width) * i64::from(wallpaper.original_height)
+        }),
+        ListSort::Added => wallpapers.sort_by_key(|wallpaper| wallpaper.added_at),
+        ListSort::Shown => wallpapers.sort_by_key(|wallpaper| wallpaper.last_set_at),
+    }
+    // Highest score, biggest, newest and most recently shown come first
+    wallpapers.reverse();
+}
+
+fn print_table(wallpapers: &[Wallpaper], fields: &[ListField]) {
+    let fields = if fields.is_empty() {
+        &DEFAULT_TABLE_FIELDS[..]
+    } else {
+        fields
+    };
+    let rows: Vec<Vec<String>> = wallpapers
+        .iter()
+        .map(|wallpaper| {
+            fields
+                .iter()
+                .map(|field| match value(wallpaper, field) {
+                    Value::Null => "-".to_owned(),
+                    Value::String(value) => value,
+                    value => value.to_string(),
+                })
+                .collect()
+        })
+        .collect();
+
+    let mut widths: Vec<usize> = fields.iter().map(|field| field.to_string().len()).collect();
+    for row in rows.iter() {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let print_row = |cells: Vec<String>| {
+        let line: Vec<String> = cells
+            .iter()
+            .zip(widths.iter())
+            .map(|(cell, width)| format!("{cell:<width$}"))
+            .collect();
+        println!("{}", line.join("  ").trim_end());
+    };
+    print_row(
+        fields
+            .iter()
+            .map(|field| field.to_string().to_uppercase())
+            .collect(),
+    );
+    for row in rows {
+        print_row(row);
+    }
+}
+
+pub fn list(
+    query: &Option<Query>,
+    format: &ListFormat,
+    sort: &Option<ListSort>,
+    reverse: bool,
+    limit: Option<usize>,
+    fields: &[ListField],
+    library: &Library,
+) -> Result<()> {
+    let query = query.clone().unwrap_or_default();
+    let mut wallpapers = library.find_wallpapers(&query)?;
+
+    if let Some(sort) = sort {
+        sort_wallpapers(&mut wallpapers, sort);
+    }
+    if reverse {
+        wallpapers.reverse();
+    }
+    if let Some(limit) = limit {
+        wallpapers.truncate(limit);
+    }
+
+    match format {
+        ListFormat::Table => print_table(&wallpapers, fields),
+        ListFormat::Json => {
+            let objects = wallpapers
+                .iter()
+                .map(|wallpaper| object(wallpaper, fields))
+                .collect::<Result<Vec<Value>>>()?;
+            println!("{}", serde_json::to_string_pretty(&objects)?);
+        }
+        ListFormat::Ndjson => {
+            for wallpaper in wallpapers.iter() {
+                println!("{}", serde_json::to_string(&object(wallpaper, fields)?)?);
+            }
+        }
+    }
+    Ok(())
+}
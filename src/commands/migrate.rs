@@ -0,0 +1,21 @@
+use std::path::PathBuf;
+
+use crate::{library, migration::SCHEMA_VERSION};
+use anyhow::Result;
+
+pub fn migrate(wallpapers_dir: PathBuf) -> Result<()> {
+    match library::migrate(&wallpapers_dir)? {
+        Some(migrated) => {
+            eprintln!(
+                "Backed up the library to {}",
+                migrated.backup_path.to_string_lossy()
+            );
+            eprintln!(
+                "Migrated the library from version {} to {SCHEMA_VERSION}, upgraded {} records",
+                migrated.from_version, migrated.records
+            );
+        }
+        None => eprintln!("The library is already at version {SCHEMA_VERSION}"),
+    }
+    Ok(())
+}
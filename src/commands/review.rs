@@ -1,138 +1,81 @@
-use std::path::PathBuf;
-
-use glob::glob;
-
 use crate::{
-    process::process_wallpapers, rating::Category, wallpaper::Wallpaper, wallpaper_history::History,
+    library::Library,
+    order::Order,
+    process::{process_wallpapers, ReviewSession},
+    query::{Condition, Query},
+    rating::Category,
+    wallpaper::Wallpaper,
 };
 use anyhow::Result;
-use tokio::{fs, select, sync::mpsc};
-use tokio_util::sync::CancellationToken;
+use tokio::{
+    select,
+    sync::mpsc::{self, Sender},
+};
 
 use super::ReviewSubcommand;
 
+async fn send_matching(
+    library: &Library,
+    query: &Query,
+    order: &Option<Order>,
+    screen_ratio: f64,
+    wallpapers_to_review_tx: Sender<Wallpaper>,
+) -> Result<()> {
+    let mut wallpapers = library.find_wallpapers(query)?;
+    if let Some(order) = order {
+        order.sort(&mut wallpapers, &order.preferences(library)?, screen_ratio);
+    }
+    for wallpaper in wallpapers {
+        wallpapers_to_review_tx.send(wallpaper).await?;
+    }
+    Ok(())
+}
+
 pub async fn review(
-    screen_width: u16,
-    screen_height: u16,
     subcommand: &ReviewSubcommand,
-    wallpapers_dir: PathBuf,
-    config_dir: PathBuf,
-    history: History,
-    token: CancellationToken,
-    set_wallpaper_command_override: Option<String>,
+    order: &Option<Order>,
+    session: ReviewSession,
 ) -> Result<()> {
     let (wallpapers_to_review_tx, wallpapers_to_review_rx) = mpsc::channel(10);
-    let history_cloned = history.clone();
+    let history_cloned = session.history.clone();
+    let screen_ratio = session.output.ratio();
 
-    match subcommand {
+    let query = match subcommand {
         ReviewSubcommand::Current => {
             if let Some(md5) = history_cloned.current() {
                 wallpapers_to_review_tx
-                    .send(Wallpaper::from_md5(&wallpapers_dir, &md5)?)
+                    .send(Wallpaper::from_md5(&session.library, &md5)?)
                     .await?;
             }
-            drop(wallpapers_to_review_tx);
-        }
-        ReviewSubcommand::Liked => {
-            tokio::task::spawn({
-                let token = token.clone();
-                let wallpapers_dir = wallpapers_dir.clone();
-
-                async move {
-                    select! {
-                        _ = token.cancelled() => {}
-                        _ = async move {
-                                let result: Result<()> = try {
-                                    for path in glob(&format!("{}/*.json", wallpapers_dir.to_str().unwrap()))?.filter_map(Result::ok) {
-                                        let content =
-                                            fs::read_to_string(path)
-                                                .await?;
-                                        let wallpaper: Wallpaper = serde_json::from_str(&content)?;
-                                        if wallpaper.category == Some(Category::Liked) {
-                                            wallpapers_to_review_tx
-                                                .send(wallpaper)
-                                                .await
-                                                ?;
-                                        }
-
-                                    }
-                                };
-                            result
-
-                    } => {}
-                    }
-                }
-            });
+            None
         }
+        ReviewSubcommand::Liked => Some(Query::from(Condition::Category(Some(Category::Liked)))),
         ReviewSubcommand::Disliked => {
-            tokio::task::spawn({
-                let token = token.clone();
-                let wallpapers_dir = wallpapers_dir.clone();
-
-                async move {
-                    select! {
-                        _ = token.cancelled() => {}
-                        _ = async move {let result: Result<()> = try {
-                            for path in glob(&format!("{}/*.json", wallpapers_dir.to_str().unwrap()))?.filter_map(Result::ok) {
-                                let content =
-                                    fs::read_to_string(path)
-                                        .await?;
-                                let wallpaper: Wallpaper = serde_json::from_str(&content)?;
-                                if wallpaper.category == Some(Category::Disliked) {
-                                    wallpapers_to_review_tx
-                                        .send(wallpaper)
-                                        .await
-                                        ?;
-                                }
-
-                            }
-                            };
-                            result
-                        } => {}
-                    }
-                }
-            });
+            Some(Query::from(Condition::Category(Some(Category::Disliked))))
         }
-        ReviewSubcommand::Borked => {
+        ReviewSubcommand::Borked => Some(Query::from(Condition::Category(Some(Category::Borked)))),
+        ReviewSubcommand::Uncategorized => Some(Query::from(Condition::Category(None))),
+        ReviewSubcommand::Query { query } => Some(query.clone()),
+    };
+
+    match query {
+        Some(query) => {
             tokio::task::spawn({
-                let token = token.clone();
-                let wallpapers_dir = wallpapers_dir.clone();
+                let token = session.token.clone();
+                let library = session.library.clone();
+                let order = order.clone();
 
                 async move {
                     select! {
                         _ = token.cancelled() => {}
-                        _ = async move {let result: Result<()> = try {
-                            for path in glob(&format!("{}/*.json", wallpapers_dir.to_str().unwrap()))?.filter_map(Result::ok) {
-                                let content =
-                                    fs::read_to_string(path)
-                                        .await?;
-                                let wallpaper: Wallpaper = serde_json::from_str(&content)?;
-                                if wallpaper.category == Some(Category::Borked) {
-                                    wallpapers_to_review_tx
-                                        .send(wallpaper)
-                                        .await
-                                        ?;
-                                }
-
-                            }};
-                            result
-                        } => {}
+                        _ = send_matching(&library, &query, &order, screen_ratio, wallpapers_to_review_tx) => {}
                     }
                 }
             });
         }
+        None => drop(wallpapers_to_review_tx),
     }
 
-    process_wallpapers(
-        wallpapers_to_review_rx,
-        token,
-        history,
-        wallpapers_dir,
-        config_dir,
-        screen_width,
-        screen_height,
-        set_wallpaper_command_override,
-    )
-    .await?;
+    process_wallpapers(wallpapers_to_review_rx, session).await?;
     Ok(())
 }
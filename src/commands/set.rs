@@ -1,81 +1,185 @@
-use std::path::{Path, PathBuf};
-
-use std::fs;
+use std::path::Path;
 
 use super::SetSubcommand;
+use crate::blacklist::Blacklist;
+use crate::config::Config;
+use crate::crop::span_wallpaper;
+use crate::library::Library;
+use crate::output::{Output, OutputError};
+use crate::query::{Condition, Query};
 use crate::rating::{Category, Rating};
 use crate::wallpaper::Wallpaper;
-use crate::wallpaper_history::save_history;
-use crate::{commands::HistorySubcommand, wallpaper::set_wallpaper, wallpaper_history::History};
+use crate::wallpaper_history::History;
+use crate::{commands::HistorySubcommand, wallpaper::set_wallpaper};
 use anyhow::Result;
-use glob::glob;
-use rand::seq::SliceRandom;
 
-pub fn set(
-    subcommand: &SetSubcommand,
-    wallpapers_dir: PathBuf,
-    config_dir: PathBuf,
-    mut history: History,
+// With the configured blacklist
+fn pick_random(
+    library: &Library,
+    config: &Config,
+    query: &Query,
+    exclude: &[String],
+) -> Result<Option<Wallpaper>> {
+    let blacklist = Blacklist::new(&config.blacklist);
+    library.random_wallpaper(query, &blacklist, exclude)
+}
+
+async fn span(
+    md5: &Option<String>,
+    query: &Option<Query>,
+    outputs: &[Output],
+    library: &Library,
+    config: &Config,
     set_wallpaper_command_override: Option<String>,
 ) -> Result<()> {
-    match subcommand {
-        SetSubcommand::File { path } => {
-            set_wallpaper(&config_dir, Path::new(path), set_wallpaper_command_override)?;
+    let wallpaper = match md5 {
+        Some(md5) => Wallpaper::from_md5(library, md5)?,
+        None => {
+            let exclude: Vec<String> = outputs
+                .iter()
+                .map(|output| library.get_history(&output.name))
+                .collect::<Result<Vec<_>>>()?
+                .iter()
+                .filter_map(History::current)
+                .collect();
+            let query = query.clone().unwrap_or_default();
+            match pick_random(library, config, &query, &exclude)? {
+                Some(wallpaper) => wallpaper,
+                None => return Ok(()),
+            }
         }
-        SetSubcommand::Md5 { md5 } => {
-            let wallpaper = Wallpaper::from_md5(&wallpapers_dir, md5)?;
-            wallpaper.set_prefered(&config_dir, set_wallpaper_command_override)?;
-            history.push(wallpaper.md5);
+    };
+
+    // Kept out of the history, going back to it would set the whole image on one output
+    let slices = span_wallpaper(&wallpaper, outputs).await?;
+    for (output, slice) in outputs.iter().zip(slices) {
+        set_wallpaper(
+            config,
+            &slice,
+            &output.name,
+            output.targeted,
+            set_wallpaper_command_override.clone(),
+        )
+        .await?;
+    }
+    library.mark_set(&wallpaper.md5)?;
+    Ok(())
+}
+
+// The outputs a set command changes, spanning always covers the whole layout. The screen size
+// overrides were applied to `output` only, they can't stand for several outputs
+pub fn targets(
+    subcommand: &SetSubcommand,
+    all_outputs: bool,
+    outputs: &[Output],
+    output: Output,
+    screen_width: Option<u16>,
+    screen_height: Option<u16>,
+) -> Result<Vec<Output>> {
+    match all_outputs || matches!(subcommand, SetSubcommand::Span { .. }) {
+        false => Ok(vec![output]),
+        true if outputs.len() == 1 => Ok(vec![output]),
+        true if screen_width.is_some() || screen_height.is_some() => {
+            Err(OutputError::ScreenSizeForAllOutputs)?
         }
-        SetSubcommand::Random { rating, category } => {
-            let mut wallpapers: Vec<PathBuf> =
-                glob(&format!("{}/*.json", wallpapers_dir.to_str().unwrap()))?
-                    .filter_map(Result::ok)
-                    .collect();
-            wallpapers.shuffle(&mut rand::thread_rng());
+        true => Ok(outputs
+            .iter()
+            .map(|output| Output {
+                targeted: true,
+                ..output.clone()
+            })
+            .collect()),
+    }
+}
 
-            for path in wallpapers.iter() {
-                let content = fs::read_to_string(path)?;
-                let wallpaper: Wallpaper = serde_json::from_str(&content)?;
-                let current = history.current();
+pub async fn set(
+    subcommand: &SetSubcommand,
+    outputs: &[Output],
+    library: &Library,
+    config: &Config,
+    set_wallpaper_command_override: Option<String>,
+) -> Result<()> {
+    if let SetSubcommand::Span { md5, query } = subcommand {
+        return span(
+            md5,
+            query,
+            outputs,
+            library,
+            config,
+            set_wallpaper_command_override,
+        )
+        .await;
+    }
 
-                if ((wallpaper.category == Some(category.to_owned()))
-                    || (category.to_owned() == Category::Any))
-                    && ((wallpaper.rating == rating.to_owned())
-                        || (rating.to_owned() == Rating::Any))
-                    && (current.is_none() || current.is_some_and(|c| c != wallpaper.md5))
-                {
-                    wallpaper.set_prefered(&config_dir, set_wallpaper_command_override)?;
-                    history.push(wallpaper.md5);
+    // Picked for the other outputs in this run, so every screen gets a different wallpaper
+    let mut picked: Vec<String> = vec![];
 
-                    break;
-                }
+    for output in outputs {
+        let mut history = library.get_history(&output.name)?;
+        let mut set_md5 = None;
+        match subcommand {
+            SetSubcommand::File { path } => {
+                set_wallpaper(
+                    config,
+                    Path::new(path),
+                    &output.name,
+                    output.targeted,
+                    set_wallpaper_command_override.clone(),
+                )
+                .await?;
             }
-        }
-        SetSubcommand::History(history_subcommand) => {
-            match history_subcommand {
-                HistorySubcommand::Previous => {
-                    if let Some(md5) = history.prev() {
-                        Wallpaper::from_md5(&wallpapers_dir, &md5)?
-                            .set_prefered(&config_dir, set_wallpaper_command_override)?;
-                    }
+            SetSubcommand::Md5 { md5 } => {
+                let wallpaper = Wallpaper::from_md5(library, md5)?;
+                wallpaper
+                    .set_prefered(config, output, set_wallpaper_command_override.clone())
+                    .await?;
+                history.push(wallpaper.md5.clone());
+                set_md5 = Some(wallpaper.md5);
+            }
+            SetSubcommand::Random {
+                rating,
+                category,
+                query,
+            } => {
+                let mut query = query.clone().unwrap_or_default();
+                if *rating != Rating::Any {
+                    query = query.and(Condition::Rating(rating.to_owned()));
                 }
-                HistorySubcommand::Next => {
-                    if let Some(md5) = history.next() {
-                        Wallpaper::from_md5(&wallpapers_dir, &md5)?
-                            .set_prefered(&config_dir, set_wallpaper_command_override)?;
-                    }
+                if *category != Category::Any {
+                    query = query.and(Condition::Category(Some(category.to_owned())));
                 }
-                HistorySubcommand::Current => {
-                    if let Some(md5) = history.current() {
-                        Wallpaper::from_md5(&wallpapers_dir, &md5)?
-                            .set_prefered(&config_dir, set_wallpaper_command_override)?;
-                    }
+
+                let exclude: Vec<String> =
+                    picked.iter().cloned().chain(history.current()).collect();
+                if let Some(wallpaper) = pick_random(library, config, &query, &exclude)? {
+                    wallpaper
+                        .set_prefered(config, output, set_wallpaper_command_override.clone())
+                        .await?;
+                    history.push(wallpaper.md5.clone());
+                    picked.push(wallpaper.md5.clone());
+                    set_md5 = Some(wallpaper.md5);
                 }
-            };
+            }
+            SetSubcommand::Span { .. } => unreachable!("span sets every output at once"),
+            SetSubcommand::History(history_subcommand) => {
+                let md5 = match history_subcommand {
+                    HistorySubcommand::Previous => history.prev(),
+                    HistorySubcommand::Next => history.next(),
+                    HistorySubcommand::Current => history.current(),
+                };
+                if let Some(md5) = md5 {
+                    Wallpaper::from_md5(library, &md5)?
+                        .set_prefered(config, output, set_wallpaper_command_override.clone())
+                        .await?;
+                    set_md5 = Some(md5);
+                }
+            }
         }
-    }
 
-    save_history(&wallpapers_dir, &history)?;
+        if let Some(md5) = set_md5 {
+            library.mark_set(&md5)?;
+        }
+        library.save_history(&output.name, &history)?;
+    }
     Ok(())
 }
@@ -0,0 +1,46 @@
+use std::fs;
+
+use crate::library::Library;
+use anyhow::Result;
+
+pub fn verify(library: &Library) -> Result<()> {
+    let mut ok = 0;
+    let mut corrupt = 0;
+    let mut missing = 0;
+
+    for wallpaper in library.wallpapers()? {
+        match fs::read(&wallpaper.downloaded_image_path) {
+            Ok(bytes) if format!("{:x}", md5::compute(&bytes)) == wallpaper.md5 => ok += 1,
+            Ok(_) => {
+                corrupt += 1;
+                println!(
+                    "corrupt\t{}\t{}",
+                    wallpaper.md5,
+                    wallpaper.downloaded_image_path.to_string_lossy()
+                );
+            }
+            Err(_) => {
+                missing += 1;
+                println!(
+                    "missing\t{}\t{}",
+                    wallpaper.md5,
+                    wallpaper.downloaded_image_path.to_string_lossy()
+                );
+            }
+        }
+
+        if let Some(crop_data) = &wallpaper.crop_data {
+            if !crop_data.cropped_image_path.exists() {
+                missing += 1;
+                println!(
+                    "missing\t{}\t{}",
+                    wallpaper.md5,
+                    crop_data.cropped_image_path.to_string_lossy()
+                );
+            }
+        }
+    }
+
+    eprintln!("{ok} ok, {corrupt} corrupt, {missing} missing");
+    Ok(())
+}
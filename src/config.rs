@@ -1,25 +1,99 @@
-use std::{fs, io::Read, path::Path};
+use std::{
+    collections::BTreeMap,
+    env,
+    fs::{self, DirBuilder, Permissions},
+    io::{self, Read},
+    os::unix::fs::{DirBuilderExt, MetadataExt, PermissionsExt},
+    path::{Path, PathBuf},
+};
 
-use anyhow::Result;
+use anyhow::{Context, Result};
+use directories::ProjectDirs;
 use serde::{Deserialize, Serialize};
+
+use crate::setter::Setter;
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
+    #[serde(default = "default_setter")]
+    pub setter: Setter,
+    #[serde(default)]
+    pub setter_output_args: BTreeMap<String, String>,
     #[serde(default = "default_set_wallpaper_command")]
     pub set_wallpaper_command: String,
+    #[serde(default = "default_get_outputs_command")]
+    pub get_outputs_command: String,
     #[serde(default = "default_get_screen_width_command")]
     pub get_screen_width_command: String,
     #[serde(default = "default_get_screen_height_command")]
     pub get_screen_height_command: String,
+    #[serde(default = "default_moebooru_sources")]
+    pub moebooru_sources: BTreeMap<String, String>,
+    #[serde(default)]
+    pub danbooru_login: Option<String>,
+    #[serde(default)]
+    pub danbooru_api_key: Option<String>,
+    #[serde(default)]
+    pub gelbooru_user_id: Option<String>,
+    #[serde(default)]
+    pub gelbooru_api_key: Option<String>,
+    #[serde(default)]
+    pub wallhaven: WallhavenConfig,
+    #[serde(default = "default_download_jobs")]
+    pub download_jobs: usize,
+    #[serde(default)]
+    pub blacklist: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WallhavenConfig {
+    #[serde(default)]
+    pub api_key: Option<String>,
+    #[serde(default = "default_wallhaven_categories")]
+    pub categories: String,
+    #[serde(default)]
+    pub ratios: Option<String>,
+    #[serde(default = "default_wallhaven_sorting")]
+    pub sorting: String,
+    #[serde(default = "default_wallhaven_pages")]
+    pub pages: u32,
+}
+
+impl Default for WallhavenConfig {
+    fn default() -> Self {
+        WallhavenConfig {
+            api_key: None,
+            categories: default_wallhaven_categories(),
+            ratios: None,
+            sorting: default_wallhaven_sorting(),
+            pages: default_wallhaven_pages(),
+        }
+    }
 }
 impl Config {
     fn new() -> Config {
         Config {
+            setter: default_setter(),
+            setter_output_args: BTreeMap::new(),
             set_wallpaper_command: default_set_wallpaper_command(),
+            get_outputs_command: default_get_outputs_command(),
             get_screen_width_command: default_get_screen_width_command(),
             get_screen_height_command: default_get_screen_height_command(),
+            moebooru_sources: default_moebooru_sources(),
+            danbooru_login: None,
+            danbooru_api_key: None,
+            gelbooru_user_id: None,
+            gelbooru_api_key: None,
+            wallhaven: WallhavenConfig::default(),
+            download_jobs: default_download_jobs(),
+            blacklist: vec![],
         }
     }
 }
+fn default_get_outputs_command() -> String {
+    r#"bash -c 'hyprctl monitors | sed -nE "s/^Monitor (\S+) .*/\1/p; s/^\s+([0-9]+x[0-9]+)@\S+ at (-?[0-9]+x-?[0-9]+)/\1 \2/p; s/^\s+scale: (\S+)/\1/p" | paste -d " " - - -'"#
+        .to_owned()
+}
+
 fn default_get_screen_width_command() -> String {
     r#"bash -c 'hyprctl monitors | head -n 2 | grep -oP "\d+(?=x.*@)"'"#.to_owned()
 }
@@ -28,8 +102,36 @@ fn default_get_screen_height_command() -> String {
     r#"bash -c 'hyprctl monitors | head -n 2 | grep -oP "(?<=x)\d+(?=@)"'"#.to_owned()
 }
 
+fn default_setter() -> Setter {
+    Setter::Command
+}
+
 fn default_set_wallpaper_command() -> String {
-    "swaybg --mode fill --image {}".to_owned()
+    "swaybg --output {output} --mode fill --image {}".to_owned()
+}
+
+fn default_moebooru_sources() -> BTreeMap<String, String> {
+    BTreeMap::from([
+        ("konachan".to_owned(), "https://konachan.net".to_owned()),
+        ("konachan.com".to_owned(), "https://konachan.com".to_owned()),
+        ("yandere".to_owned(), "https://yande.re".to_owned()),
+    ])
+}
+
+fn default_download_jobs() -> usize {
+    4
+}
+
+fn default_wallhaven_categories() -> String {
+    "010".to_owned()
+}
+
+fn default_wallhaven_sorting() -> String {
+    "toplist".to_owned()
+}
+
+fn default_wallhaven_pages() -> u32 {
+    10
 }
 
 pub fn get_config(config_dir: &Path) -> Result<Config> {
@@ -38,9 +140,32 @@ pub fn get_config(config_dir: &Path) -> Result<Config> {
 
     let mut contents = String::new();
     file.read_to_string(&mut contents)?;
-    if let Ok(config) = serde_json::from_str(&contents) {
-        Ok(config)
-    } else {
-        Ok(Config::new())
+    // Falling back to the defaults on a typo would quietly drop the blacklist
+    if contents.trim().is_empty() {
+        return Ok(Config::new());
+    }
+    serde_json::from_str(&contents)
+        .with_context(|| format!("Invalid config {}", path.to_string_lossy()))
+}
+
+// Pid files of the setters and the daemon socket, created if missing. Only this user may write
+// there, otherwise anyone could plant a pid file to have a process of this user killed
+pub fn runtime_dir() -> io::Result<PathBuf> {
+    let uid = fs::metadata("/proc/self")?.uid();
+    let dir = ProjectDirs::from("com", "Verdek", "aniwall")
+        .and_then(|pd| pd.runtime_dir().map(Path::to_path_buf))
+        .unwrap_or_else(|| env::temp_dir().join(format!("aniwall-{uid}")));
+    DirBuilder::new().recursive(true).mode(0o700).create(&dir)?;
+
+    let metadata = fs::symlink_metadata(&dir)?;
+    if !metadata.is_dir() || metadata.uid() != uid {
+        return Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            format!("{} is not a directory of this user", dir.to_string_lossy()),
+        ));
+    }
+    if metadata.mode() & 0o077 != 0 {
+        fs::set_permissions(&dir, Permissions::from_mode(0o700))?;
     }
+    Ok(dir)
 }
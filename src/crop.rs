@@ -1,45 +1,246 @@
+use crate::output::Output;
 use crate::wallpaper::Prefered;
 use crate::wallpaper::{CropData, Wallpaper};
 use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
 use tokio::fs;
 
+pub fn get_image_dimensions(path: &Path) -> Result<(i32, i32)> {
+    let output = Command::new("magick")
+        .args([
+            "identify",
+            "-format",
+            "%w %h",
+            &format!("{}[0]", path.to_string_lossy()),
+        ])
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .context("magick command failed to start, make sure to have ImageMagick installed")?;
+    output.status.exit_ok()?;
+
+    let output = String::from_utf8(output.stdout)?;
+    let (width, height) = output
+        .trim()
+        .split_once(' ')
+        .context("Unexpected magick identify output")?;
+    Ok((width.parse()?, height.parse()?))
+}
+
+fn sibling_path(downloaded_image_path: &Path, suffix: &str) -> PathBuf {
+    let stem = downloaded_image_path.file_stem().unwrap().to_str().unwrap();
+    let mut pathbuf = downloaded_image_path.to_path_buf();
+    pathbuf.set_file_name(format!("{stem}{suffix}"));
+
+    if let Some(ext) = downloaded_image_path.extension() {
+        pathbuf.set_extension(ext);
+    }
+    pathbuf
+}
+
+pub fn cropped_image_path(downloaded_image_path: &Path, size: Option<(u16, u16)>) -> PathBuf {
+    match size {
+        Some((width, height)) => {
+            sibling_path(downloaded_image_path, &format!("_cropped_{width}x{height}"))
+        }
+        None => sibling_path(downloaded_image_path, "_cropped"),
+    }
+}
+
 pub async fn crop_wallpaper(
     mut wallpaper: Wallpaper,
     width: u16,
     height: u16,
 ) -> Result<Wallpaper> {
-    let mut cropped_image_pathbuf = wallpaper.downloaded_image_path.clone();
-    cropped_image_pathbuf.set_file_name(format!(
-        "{}_cropped",
-        wallpaper
-            .downloaded_image_path
-            .file_stem()
-            .unwrap()
-            .to_str()
-            .unwrap()
-    ));
-
-    if let Some(ext) = wallpaper.downloaded_image_path.extension() {
-        cropped_image_pathbuf.set_extension(ext);
-    }
+    let cropped_image_pathbuf = cropped_image_path(&wallpaper.downloaded_image_path, None);
 
     if cropped_image_pathbuf.exists() {
         return Ok(wallpaper);
     }
 
-    let cropped_image_path_string: String = cropped_image_pathbuf.to_string_lossy().into();
+    let (x_off, y_off) = crop_image(
+        &wallpaper.downloaded_image_path,
+        &cropped_image_pathbuf,
+        width,
+        height,
+    )
+    .await?;
+
+    wallpaper.crop_data = Some(CropData {
+        cropped_image_path: cropped_image_pathbuf,
+        crop_offset_x: x_off,
+        crop_offset_y: y_off,
+    });
+
+    wallpaper.prefered = Prefered::Cropped;
+    Ok(wallpaper)
+}
+
+// The crop made during review fits the screen it was reviewed on, other resolutions get their own
+// crop next to it
+pub async fn crop_for_output(
+    wallpaper: &Wallpaper,
+    crop_data: &CropData,
+    output: &Output,
+) -> Result<PathBuf> {
+    let size = (i32::from(output.width), i32::from(output.height));
+    if get_image_dimensions(&crop_data.cropped_image_path).ok() == Some(size) {
+        return Ok(crop_data.cropped_image_path.clone());
+    }
+
+    let cropped_image_pathbuf = cropped_image_path(
+        &wallpaper.downloaded_image_path,
+        Some((output.width, output.height)),
+    );
+    if !cropped_image_pathbuf.exists() {
+        crop_image(
+            &wallpaper.downloaded_image_path,
+            &cropped_image_pathbuf,
+            output.width,
+            output.height,
+        )
+        .await?;
+    }
+    Ok(cropped_image_pathbuf)
+}
+
+// Outputs are placed in logical pixels, a scaled output covers its resolution divided by the scale
+fn logical_size(output: &Output) -> (i32, i32) {
+    (
+        (f64::from(output.width) / output.scale).round() as i32,
+        (f64::from(output.height) / output.scale).round() as i32,
+    )
+}
+
+// Smallest rectangle covering every output in logical pixels, as (x, y, width, height)
+fn canvas(outputs: &[Output]) -> (i32, i32, i32, i32) {
+    let left = outputs.iter().map(|output| output.x).min().unwrap_or(0);
+    let top = outputs.iter().map(|output| output.y).min().unwrap_or(0);
+    let right = outputs
+        .iter()
+        .map(|output| output.x + logical_size(output).0)
+        .max()
+        .unwrap_or(0);
+    let bottom = outputs
+        .iter()
+        .map(|output| output.y + logical_size(output).1)
+        .max()
+        .unwrap_or(0);
+    (left, top, right - left, bottom - top)
+}
+
+// Slices are cached per layout, moving or resizing an output makes new ones
+fn layout_id(outputs: &[Output]) -> String {
+    let layout: String = outputs
+        .iter()
+        .map(|output| {
+            format!(
+                "{} {}x{}+{}+{}@{};",
+                output.name, output.width, output.height, output.x, output.y, output.scale
+            )
+        })
+        .collect();
+    format!("{:x}", md5::compute(layout.as_bytes()))[..8].to_owned()
+}
+
+// Crops the wallpaper to a canvas covering every output and cuts it into one slice per output,
+// returned in the order of the outputs
+pub async fn span_wallpaper(wallpaper: &Wallpaper, outputs: &[Output]) -> Result<Vec<PathBuf>> {
+    let layout = layout_id(outputs);
+    let slice_paths: Vec<PathBuf> = outputs
+        .iter()
+        .map(|output| {
+            sibling_path(
+                &wallpaper.downloaded_image_path,
+                &format!("_cropped_span_{layout}_{}", output.name),
+            )
+        })
+        .collect();
+    if slice_paths.iter().all(|path| path.exists()) {
+        return Ok(slice_paths);
+    }
+
+    // Rendered at the highest scale, so no output gets an upscaled slice
+    let (left, top, width, height) = canvas(outputs);
+    let scale = outputs
+        .iter()
+        .map(|output| output.scale)
+        .fold(1.0, f64::max);
+    let scaled = |value: i32| (f64::from(value) * scale).round() as i32;
+    let canvas_path = sibling_path(
+        &wallpaper.downloaded_image_path,
+        &format!("_cropped_span_{layout}"),
+    );
+    crop_image(
+        &wallpaper.downloaded_image_path,
+        &canvas_path,
+        u16::try_from(scaled(width))?,
+        u16::try_from(scaled(height))?,
+    )
+    .await?;
+
+    for (output, slice_path) in outputs.iter().zip(&slice_paths) {
+        let canvas_path_string: String = canvas_path.to_string_lossy().into();
+        let slice_path_string: String = slice_path.to_string_lossy().into();
+        let (logical_width, logical_height) = logical_size(output);
+        let geometry = format!(
+            "{}x{}+{}+{}",
+            scaled(logical_width),
+            scaled(logical_height),
+            scaled(output.x - left),
+            scaled(output.y - top)
+        );
+        let size = format!("{}x{}!", output.width, output.height);
+        let join = tokio::task::spawn_blocking(move || -> Result<()> {
+            Command::new("magick")
+                .args([
+                    &canvas_path_string,
+                    "-crop",
+                    &geometry,
+                    "+repage",
+                    "-resize",
+                    &size,
+                    &slice_path_string,
+                ])
+                .stdin(Stdio::null())
+                .spawn()
+                .context("magick command failed to start, make sure to have ImageMagick installed")?
+                .wait()?
+                .exit_ok()?;
+            Ok(())
+        });
+        join.await??;
+    }
+
+    fs::remove_file(&canvas_path).await?;
+    Ok(slice_paths)
+}
+
+async fn crop_image(
+    downloaded_image_path: &Path,
+    cropped_image_path: &Path,
+    width: u16,
+    height: u16,
+) -> Result<(i32, i32)> {
+    let cropped_image_path_string: String = cropped_image_path.to_string_lossy().into();
+
+    let downloaded_image_path_string: String = downloaded_image_path.to_string_lossy().into();
 
-    let downloaded_image_path_string: String =
-        wallpaper.downloaded_image_path.to_string_lossy().into();
+    // Intermediates are kept next to the target, other crops may be running at the same time
+    let resize_path = cropped_image_path.with_extension("resize.png");
+    let canny_path = cropped_image_path.with_extension("canny.png");
+    let resize_path_string: String = resize_path.to_string_lossy().into();
+    let canny_path_string: String = canny_path.to_string_lossy().into();
 
+    let (resize, canny) = (resize_path_string.clone(), canny_path_string.clone());
     let join = tokio::task::spawn_blocking(move || -> Result<()> {
         Command::new("magick")
             .args([
                 &downloaded_image_path_string,
                 "-resize",
                 &format!("{width}x{height}^"),
-                "resize.png",
+                &resize,
             ])
             .stdin(Stdio::null())
             .spawn()
@@ -50,10 +251,11 @@ pub async fn crop_wallpaper(
     });
     join.await??;
 
+    let resize = resize_path_string.clone();
     let join = tokio::task::spawn_blocking(move || -> Result<()> {
         Command::new("convert")
             .args([
-                "resize.png",
+                &resize,
                 "-canny",
                 "0x1+10%+30%",
                 "-separate",
@@ -64,7 +266,7 @@ pub async fn crop_wallpaper(
                 "-equalize",
                 "-resize",
                 "10%x10%",
-                "canny.png",
+                &canny,
             ])
             .stdin(Stdio::null())
             .spawn()
@@ -84,7 +286,7 @@ pub async fn crop_wallpaper(
                 "-subimage-search",
                 "-dissimilarity-threshold",
                 "1",
-                "canny.png",
+                &canny_path_string,
                 "(",
                 "-size",
                 &format!("{}x{}", width / 10, height / 10),
@@ -106,11 +308,11 @@ pub async fn crop_wallpaper(
     let x_off: i32 = x_off_str.trim().parse()?;
     let y_off: i32 = y_off_str.parse()?;
 
-    fs::remove_file("canny.png").await?;
+    fs::remove_file(&canny_path).await?;
     let join = tokio::task::spawn_blocking(move || -> Result<()> {
         Command::new("convert")
             .args([
-                "resize.png",
+                &resize_path_string,
                 "-crop",
                 &format!("{width}x{height}+{x_off}+{y_off}"),
                 "+repage",
@@ -125,14 +327,6 @@ pub async fn crop_wallpaper(
     });
     join.await??;
 
-    fs::remove_file("resize.png").await?;
-
-    wallpaper.crop_data = Some(CropData {
-        cropped_image_path: cropped_image_pathbuf,
-        crop_offset_x: x_off,
-        crop_offset_y: y_off,
-    });
-
-    wallpaper.prefered = Prefered::Cropped;
-    Ok(wallpaper)
+    fs::remove_file(&resize_path).await?;
+    Ok((x_off, y_off))
 }
@@ -0,0 +1,159 @@
+use std::{
+    env,
+    io::{BufRead, BufReader, Write},
+    os::unix::net::UnixStream,
+    path::{self, Path, PathBuf},
+    sync::OnceLock,
+    time::Duration,
+};
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::{config::runtime_dir, output::Output};
+
+// A daemon that takes longer to answer is treated as gone, the command then runs in this process
+const ANSWER_TIMEOUT: Duration = Duration::from_secs(2);
+// Running a command waits for the other clients and may crop or set several wallpapers
+const RUN_TIMEOUT: Duration = Duration::from_secs(60);
+
+// Library and config dirs of the daemon this process talks to, set once it answered
+static CONNECTED: OnceLock<(PathBuf, PathBuf)> = OnceLock::new();
+
+// One json object per line each way, a request gets exactly one response
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Message {
+    // A daemon only answers for the library and config it was started on
+    pub wallpapers_dir: PathBuf,
+    pub config_dir: PathBuf,
+    #[serde(flatten)]
+    pub request: Request,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(tag = "request", rename_all = "snake_case")]
+pub enum Request {
+    Outputs,
+    // A whole `set` or `get` command line, relative paths are resolved against `cwd`
+    Run {
+        args: Vec<String>,
+        cwd: PathBuf,
+    },
+    SetWallpaper {
+        path: PathBuf,
+        output: String,
+        #[serde(default)]
+        targeted: bool,
+        command: Option<String>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum Response {
+    // `output` is what the command would have printed
+    Ok {
+        #[serde(default)]
+        output: String,
+    },
+    Outputs {
+        outputs: Vec<Output>,
+    },
+    Error {
+        message: String,
+    },
+    OtherLibrary {
+        wallpapers_dir: PathBuf,
+        config_dir: PathBuf,
+    },
+}
+
+pub fn socket_path() -> std::io::Result<PathBuf> {
+    Ok(runtime_dir()?.join("aniwall.sock"))
+}
+
+// None when no daemon is listening or it serves another library, the caller then does the work
+// itself. Once the request is written the daemon may act on it, so no answer is an error
+fn send(
+    wallpapers_dir: &Path,
+    config_dir: &Path,
+    request: Request,
+    timeout: Duration,
+) -> Option<Result<Response>> {
+    let mut stream = UnixStream::connect(socket_path().ok()?).ok()?;
+    stream.set_read_timeout(Some(timeout)).ok()?;
+    stream.set_write_timeout(Some(timeout)).ok()?;
+    let message = Message {
+        wallpapers_dir: wallpapers_dir.canonicalize().ok()?,
+        config_dir: config_dir.canonicalize().ok()?,
+        request,
+    };
+    let mut line = serde_json::to_string(&message).ok()?;
+    line.push('\n');
+    stream.write_all(line.as_bytes()).ok()?;
+
+    let mut line = String::new();
+    let response = match BufReader::new(stream).read_line(&mut line) {
+        Ok(_) => serde_json::from_str(&line).ok(),
+        Err(_) => None,
+    };
+    match response {
+        Some(Response::OtherLibrary { .. }) => None,
+        Some(response) => Some(Ok(response)),
+        None => Some(Err(anyhow!("The daemon did not answer"))),
+    }
+}
+
+// The outputs of a daemon serving this library, remembering it for the requests after
+pub fn connect(wallpapers_dir: &Path, config_dir: &Path) -> Option<Vec<Output>> {
+    match send(wallpapers_dir, config_dir, Request::Outputs, ANSWER_TIMEOUT)? {
+        Ok(Response::Outputs { outputs }) => {
+            let _ = CONNECTED.set((wallpapers_dir.to_path_buf(), config_dir.to_path_buf()));
+            Some(outputs)
+        }
+        _ => None,
+    }
+}
+
+// Runs this process' command line in the daemon and prints its output, None when there is none
+pub fn forward(wallpapers_dir: &Path, config_dir: &Path) -> Option<Result<()>> {
+    let request = Request::Run {
+        args: env::args().skip(1).collect(),
+        cwd: env::current_dir().ok()?,
+    };
+    let response = match send(wallpapers_dir, config_dir, request, RUN_TIMEOUT)? {
+        Ok(response) => response,
+        Err(err) => return Some(Err(err)),
+    };
+    match response {
+        Response::Ok { output } => {
+            if !output.is_empty() {
+                println!("{output}");
+            }
+            Some(Ok(()))
+        }
+        Response::Error { message } => Some(Err(anyhow!(message))),
+        _ => Some(Err(anyhow!("Unexpected response from the daemon"))),
+    }
+}
+
+// Lets the daemon start the setter so it can replace it later, None when not connected
+pub fn set_wallpaper(
+    path: &Path,
+    output: &str,
+    targeted: bool,
+    command: Option<String>,
+) -> Option<Result<()>> {
+    let (wallpapers_dir, config_dir) = CONNECTED.get()?;
+    let request = Request::SetWallpaper {
+        path: path::absolute(path).ok()?,
+        output: output.to_owned(),
+        targeted,
+        command,
+    };
+    match send(wallpapers_dir, config_dir, request, RUN_TIMEOUT)? {
+        Ok(Response::Error { message }) => Some(Err(anyhow!(message))),
+        Ok(_) => Some(Ok(())),
+        Err(err) => Some(Err(err)),
+    }
+}
@@ -1,52 +1,300 @@
-use futures::StreamExt;
+use futures::{stream, StreamExt};
 
-use crate::wallpaper::{KonachanWallpaper, Wallpaper};
-use anyhow::Result;
-use std::path::Path;
-use tokio::{fs, fs::File, sync::mpsc};
+use crate::image_format::ImageFormat;
+use crate::library::Library;
+use crate::source::{Post, WallpaperSource};
+use crate::wallpaper::Wallpaper;
+use anyhow::{Context, Result};
+use reqwest::{
+    header::{CONTENT_TYPE, RANGE},
+    StatusCode,
+};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+use tokio::{
+    fs::{self, File, OpenOptions},
+    io::AsyncWriteExt,
+    sync::mpsc,
+    time,
+};
+
+const MAX_RETRIES: u32 = 5;
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Clone, Default)]
+pub struct DownloadLimits {
+    pub count: Option<usize>,
+    pub bytes: Option<u64>,
+}
+
+impl DownloadLimits {
+    fn reached(&self, count: usize, bytes: u64) -> bool {
+        self.count.is_some_and(|limit| count >= limit)
+            || self.bytes.is_some_and(|limit| bytes >= limit)
+    }
+
+    fn exceeded(&self, count: usize, bytes: u64) -> bool {
+        self.count.is_some_and(|limit| count > limit)
+            || self.bytes.is_some_and(|limit| bytes > limit)
+    }
+}
+
+// Still at `partial_path` and not in the library, the limits may turn it down
+struct Fetched {
+    wallpaper: Wallpaper,
+    partial_path: PathBuf,
+    bytes: u64,
+}
+
+enum Downloaded {
+    Fetched(Fetched),
+    Uncategorized(Wallpaper),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum DownloadError {
+    #[error("Server refused to resume the partial download")]
+    RangeNotSatisfiable,
+    #[error("Downloaded file hashes to {actual}, expected {expected}")]
+    Md5Mismatch { expected: String, actual: String },
+}
+
+async fn fetch(
+    client: &reqwest::Client,
+    url: &str,
+    partial_path: &Path,
+) -> Result<(md5::Digest, Option<ImageFormat>)> {
+    let offset = fs::metadata(partial_path)
+        .await
+        .map(|metadata| metadata.len())
+        .unwrap_or(0);
+
+    let mut request = client.get(url);
+    if offset > 0 {
+        request = request.header(RANGE, format!("bytes={offset}-"));
+    }
+    let response = request.send().await?;
+
+    if response.status() == StatusCode::RANGE_NOT_SATISFIABLE {
+        fs::remove_file(partial_path).await?;
+        Err(DownloadError::RangeNotSatisfiable)?
+    }
+    let response = response.error_for_status()?;
+    let format = response
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|content_type| content_type.to_str().ok())
+        .and_then(ImageFormat::from_content_type);
+
+    let mut context = md5::Context::new();
+    let mut dest = if offset > 0 && response.status() == StatusCode::PARTIAL_CONTENT {
+        context.consume(fs::read(partial_path).await?);
+        OpenOptions::new().append(true).open(partial_path).await?
+    } else {
+        File::create(partial_path).await?
+    };
+
+    let mut content = response.bytes_stream();
+    while let Some(chunk) = content.next().await {
+        let chunk = chunk?;
+        context.consume(&chunk);
+        dest.write_all(&chunk).await?;
+    }
+    dest.flush().await?;
+    dest.sync_all().await?;
+
+    Ok((context.compute(), format))
+}
+
+async fn fetch_verified(
+    client: &reqwest::Client,
+    url: &str,
+    partial_path: &Path,
+    expected_md5: Option<&str>,
+) -> Result<(md5::Digest, Option<ImageFormat>)> {
+    let (digest, format) = fetch(client, url, partial_path).await?;
+    let actual = format!("{:x}", digest);
+    match expected_md5 {
+        Some(expected) if expected != actual => {
+            fs::remove_file(partial_path).await?;
+            Err(DownloadError::Md5Mismatch {
+                expected: expected.to_owned(),
+                actual,
+            })?
+        }
+        _ => Ok((digest, format)),
+    }
+}
+
+// A dropped connection or a struggling server may do better next time, a missing file or one that
+// was replaced on the site won't
+fn is_transient(err: &anyhow::Error) -> bool {
+    if let Some(DownloadError::RangeNotSatisfiable) = err.downcast_ref() {
+        return true;
+    }
+    match err.downcast_ref::<reqwest::Error>() {
+        Some(err) if err.is_builder() => false,
+        Some(err) => err.status().is_none_or(|status| {
+            status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS
+        }),
+        None => false,
+    }
+}
+
+async fn fetch_with_retries(
+    client: &reqwest::Client,
+    url: &str,
+    partial_path: &Path,
+    expected_md5: Option<&str>,
+) -> Result<(md5::Digest, Option<ImageFormat>)> {
+    let mut retries = 0;
+    loop {
+        match fetch_verified(client, url, partial_path, expected_md5).await {
+            Ok(fetched) => return Ok(fetched),
+            Err(err) if retries < MAX_RETRIES && is_transient(&err) => {
+                let backoff = INITIAL_BACKOFF * 2u32.pow(retries);
+                retries += 1;
+                eprintln!("{err:#}, retrying {url} in {}s", backoff.as_secs());
+                time::sleep(backoff).await;
+            }
+            Err(err) => return Err(err),
+        }
+    }
+}
+
+fn already_downloaded(wallpaper: Wallpaper) -> Option<Downloaded> {
+    match wallpaper.category {
+        Some(_) => None,
+        None => Some(Downloaded::Uncategorized(wallpaper)),
+    }
+}
+
+async fn download_wallpaper(
+    client: &reqwest::Client,
+    source: &dyn WallpaperSource,
+    post: Post,
+    library: &Library,
+    known_posts: &HashMap<(String, String), String>,
+) -> Result<Option<Downloaded>> {
+    let md5 = post.md5.clone().or_else(|| {
+        known_posts
+            .get(&(post.source.clone(), post.id.clone()))
+            .cloned()
+    });
+    let downloaded = match &md5 {
+        Some(md5) => library.get_wallpaper(md5)?,
+        None => None,
+    };
+
+    if let Some(wallpaper) = downloaded {
+        return Ok(already_downloaded(wallpaper));
+    }
+
+    let partial_path = match &md5 {
+        Some(md5) => library.dir().join(format!("{md5}.part")),
+        None => library
+            .dir()
+            .join(format!("{}_{}.part", post.source, post.id)),
+    };
+    let url = source.download_url(&post);
+    let (digest, content_type_format) =
+        fetch_with_retries(client, &url, &partial_path, md5.as_deref()).await?;
+
+    let format = ImageFormat::from_path(&partial_path)
+        .or(content_type_format)
+        .or(ImageFormat::from_url(&url))
+        .unwrap_or(ImageFormat::Png);
+    let md5 = match md5 {
+        Some(md5) => md5,
+        None => {
+            // Posts without an md5 are only recognized once fetched, keep the reviewed copy
+            let md5 = format!("{:x}", digest);
+            if let Some(wallpaper) = library.get_wallpaper(&md5)? {
+                fs::remove_file(&partial_path).await?;
+                return Ok(already_downloaded(wallpaper));
+            }
+            md5
+        }
+    };
+    let path = library.dir().join(&md5).with_extension(format.extension());
+    let bytes = fs::metadata(&partial_path).await?.len();
+    Ok(Some(Downloaded::Fetched(Fetched {
+        wallpaper: Wallpaper::from_post(post, md5, path),
+        partial_path,
+        bytes,
+    })))
+}
+
+async fn keep(library: &Library, fetched: &Fetched) -> Result<()> {
+    fs::rename(
+        &fetched.partial_path,
+        &fetched.wallpaper.downloaded_image_path,
+    )
+    .await?;
+    library.save_wallpaper(&fetched.wallpaper)
+}
 
 pub async fn download_wallpapers(
-    wallpaper_list: Vec<KonachanWallpaper>,
-    wallpapers_dir: &Path,
+    source: &dyn WallpaperSource,
+    wallpaper_list: Vec<Post>,
+    library: &Library,
+    jobs: usize,
+    limits: DownloadLimits,
     tx: mpsc::Sender<Wallpaper>,
 ) -> Result<()> {
-    for konachan_wallpaper in wallpaper_list {
-        let path = wallpapers_dir
-            .join(&konachan_wallpaper.md5)
-            .with_extension("png");
-        let wallpaper = if wallpapers_dir
-            .join(&konachan_wallpaper.md5)
-            .with_extension("json")
-            .exists()
-        {
-            let wallpaper = Wallpaper::from_md5(wallpapers_dir, &konachan_wallpaper.md5)?;
-            match wallpaper.category {
-                Some(_) => continue,
-                None => wallpaper,
+    let known_posts = if wallpaper_list.iter().any(|post| post.md5.is_none()) {
+        library.known_posts()?
+    } else {
+        HashMap::new()
+    };
+    let client = reqwest::Client::new();
+
+    let mut downloads = stream::iter(wallpaper_list)
+        .map(|post| {
+            let description = format!("{} post {}", post.source, post.id);
+            let client = &client;
+            let known_posts = &known_posts;
+            async move {
+                download_wallpaper(client, source, post, library, known_posts)
+                    .await
+                    .with_context(|| format!("Failed to download {description}"))
             }
-        } else {
-            let mut dest = File::create(&path).await?;
-
-            let mut content = reqwest::get(&konachan_wallpaper.file_url)
-                .await?
-                .bytes_stream();
-            while let Some(chunk) = content.next().await {
-                tokio::io::copy(&mut chunk?.as_ref(), &mut dest).await?;
+        })
+        .buffered(jobs);
+
+    let mut fetched = 0;
+    let mut fetched_bytes = 0;
+    while let Some(downloaded) = downloads.next().await {
+        let wallpaper = match downloaded {
+            // Checked before keeping it, so the last one can't go over --max-bytes
+            Ok(Some(Downloaded::Fetched(new))) => {
+                if limits.exceeded(fetched + 1, fetched_bytes + new.bytes) {
+                    fs::remove_file(&new.partial_path).await?;
+                    break;
+                }
+                if let Err(err) = keep(library, &new).await {
+                    eprintln!("{err:#}");
+                    continue;
+                }
+                fetched += 1;
+                fetched_bytes += new.bytes;
+                new.wallpaper
             }
-            let wallpaper = Wallpaper::from_konachan(konachan_wallpaper.clone(), path);
-
-            if let Ok(json) = serde_json::to_string(&wallpaper) {
-                fs::write(
-                    wallpapers_dir
-                        .join(&wallpapers_dir.join(&wallpaper.md5).with_extension("json"))
-                        .as_path(),
-                    json.as_bytes(),
-                )
-                .await?;
+            Ok(Some(Downloaded::Uncategorized(wallpaper))) => wallpaper,
+            Ok(None) => continue,
+            Err(err) => {
+                eprintln!("{err:#}");
+                continue;
             }
-            wallpaper
         };
         let _ = tx.send(wallpaper).await;
+
+        if limits.reached(fetched, fetched_bytes) {
+            break;
+        }
     }
     Ok(())
 }
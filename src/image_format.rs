@@ -0,0 +1,110 @@
+use std::{fmt, fs::File, io::Read, path::Path};
+
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd, Eq, Ord, Hash)]
+pub enum ImageFormat {
+    Png,
+    Jpeg,
+    Gif,
+    Webp,
+    Bmp,
+}
+
+impl ImageFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ImageFormat::Png => "png",
+            ImageFormat::Jpeg => "jpg",
+            ImageFormat::Gif => "gif",
+            ImageFormat::Webp => "webp",
+            ImageFormat::Bmp => "bmp",
+        }
+    }
+
+    pub fn from_extension(extension: &str) -> Option<ImageFormat> {
+        match extension.to_lowercase().as_str() {
+            "png" => Some(ImageFormat::Png),
+            "jpg" | "jpeg" | "jpe" => Some(ImageFormat::Jpeg),
+            "gif" => Some(ImageFormat::Gif),
+            "webp" => Some(ImageFormat::Webp),
+            "bmp" => Some(ImageFormat::Bmp),
+            _ => None,
+        }
+    }
+
+    pub fn from_magic(bytes: &[u8]) -> Option<ImageFormat> {
+        match bytes {
+            [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A, ..] => Some(ImageFormat::Png),
+            [0xFF, 0xD8, 0xFF, ..] => Some(ImageFormat::Jpeg),
+            [b'G', b'I', b'F', b'8', b'7' | b'9', b'a', ..] => Some(ImageFormat::Gif),
+            [b'R', b'I', b'F', b'F', _, _, _, _, b'W', b'E', b'B', b'P', ..] => {
+                Some(ImageFormat::Webp)
+            }
+            [b'B', b'M', ..] => Some(ImageFormat::Bmp),
+            _ => None,
+        }
+    }
+
+    pub fn from_content_type(content_type: &str) -> Option<ImageFormat> {
+        let mime = content_type.split(';').next()?.trim();
+        match mime.to_lowercase().as_str() {
+            "image/png" => Some(ImageFormat::Png),
+            "image/jpeg" | "image/jpg" => Some(ImageFormat::Jpeg),
+            "image/gif" => Some(ImageFormat::Gif),
+            "image/webp" => Some(ImageFormat::Webp),
+            "image/bmp" | "image/x-bmp" => Some(ImageFormat::Bmp),
+            _ => None,
+        }
+    }
+
+    pub fn from_url(url: &str) -> Option<ImageFormat> {
+        let path = url.split(['?', '#']).next()?;
+        let (_, extension) = path.rsplit_once('/')?.1.rsplit_once('.')?;
+        ImageFormat::from_extension(extension)
+    }
+
+    pub fn from_path(path: &Path) -> Option<ImageFormat> {
+        let mut header = [0; 12];
+        let mut file = File::open(path).ok()?;
+        let read = file.read(&mut header).ok()?;
+        ImageFormat::from_magic(&header[..read])
+    }
+}
+
+impl fmt::Display for ImageFormat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.extension())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_magic_bytes() {
+        let png = [
+            0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A, 0, 0, 0, 0x0D,
+        ];
+        assert_eq!(ImageFormat::from_magic(&png), Some(ImageFormat::Png));
+        assert_eq!(
+            ImageFormat::from_magic(&[0xFF, 0xD8, 0xFF, 0xE0]),
+            Some(ImageFormat::Jpeg)
+        );
+        assert_eq!(ImageFormat::from_magic(b"GIF89a"), Some(ImageFormat::Gif));
+        assert_eq!(
+            ImageFormat::from_magic(b"RIFF\x24\0\0\0WEBPVP8 "),
+            Some(ImageFormat::Webp)
+        );
+        assert_eq!(ImageFormat::from_magic(b"BM\x36\0"), Some(ImageFormat::Bmp));
+    }
+
+    #[test]
+    fn rejects_other_content() {
+        assert_eq!(ImageFormat::from_magic(b""), None);
+        assert_eq!(ImageFormat::from_magic(b"<!DOCTYPE html>"), None);
+        // A RIFF container that isn't webp, like a wav file
+        assert_eq!(ImageFormat::from_magic(b"RIFF\x24\0\0\0WAVEfmt "), None);
+        // Cut off before the signature ends
+        assert_eq!(ImageFormat::from_magic(&[0x89, b'P', b'N', b'G']), None);
+    }
+}
@@ -0,0 +1,502 @@
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use glob::glob;
+use rusqlite::{params, params_from_iter, types::Value, Connection, OptionalExtension};
+
+use crate::{
+    blacklist::Blacklist, migration, output::DEFAULT_OUTPUT, preference::Preferences, query::Query,
+    rating::Category, wallpaper::Wallpaper, wallpaper_history::History,
+};
+
+const DATABASE_FILE: &str = "library.db";
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS wallpapers (
+        md5 TEXT PRIMARY KEY,
+        source TEXT NOT NULL,
+        post_id TEXT,
+        category TEXT,
+        rating TEXT NOT NULL,
+        score INTEGER NOT NULL,
+        width INTEGER NOT NULL,
+        height INTEGER NOT NULL,
+        added_at INTEGER NOT NULL,
+        data TEXT NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS wallpapers_category ON wallpapers (category);
+    CREATE INDEX IF NOT EXISTS wallpapers_post ON wallpapers (source, post_id);
+    CREATE TABLE IF NOT EXISTS wallpaper_tags (
+        md5 TEXT NOT NULL,
+        tag TEXT NOT NULL,
+        PRIMARY KEY (md5, tag)
+    );
+    CREATE INDEX IF NOT EXISTS wallpaper_tags_tag ON wallpaper_tags (tag);
+    CREATE TABLE IF NOT EXISTS history (
+        output TEXT NOT NULL,
+        position INTEGER NOT NULL,
+        md5 TEXT NOT NULL,
+        PRIMARY KEY (output, position)
+    );
+    CREATE TABLE IF NOT EXISTS meta (
+        key TEXT PRIMARY KEY,
+        value TEXT NOT NULL
+    );
+";
+
+fn open(wallpapers_dir: &Path) -> Result<Connection> {
+    fs::create_dir_all(wallpapers_dir)?;
+    let mut connection = Connection::open(wallpapers_dir.join(DATABASE_FILE))?;
+
+    if is_empty(&connection)? {
+        let transaction = connection.transaction()?;
+        transaction.execute_batch(SCHEMA)?;
+        migration::set_schema_version(&transaction, migration::SCHEMA_VERSION)?;
+        import_json(&transaction, wallpapers_dir)?;
+        transaction.commit()?;
+    } else {
+        migration::check_schema(&connection)?;
+    }
+    Ok(connection)
+}
+
+fn is_empty(connection: &Connection) -> Result<bool> {
+    Ok(connection.query_row(
+        "SELECT count(*) = 0 FROM sqlite_master WHERE type = 'table' AND name = 'wallpapers'",
+        [],
+        |row| row.get(0),
+    )?)
+}
+
+fn get_meta(connection: &Connection, key: &str) -> Result<Option<String>> {
+    Ok(connection
+        .query_row("SELECT value FROM meta WHERE key = ?1", [key], |row| {
+            row.get(0)
+        })
+        .optional()?)
+}
+
+fn set_meta(connection: &Connection, key: &str, value: &str) -> Result<()> {
+    connection.execute(
+        "INSERT OR REPLACE INTO meta (key, value) VALUES (?1, ?2)",
+        [key, value],
+    )?;
+    Ok(())
+}
+
+fn json_paths(wallpapers_dir: &Path) -> Result<Vec<PathBuf>> {
+    Ok(
+        glob(&format!("{}/*.json", wallpapers_dir.to_str().unwrap()))?
+            .filter_map(Result::ok)
+            .collect(),
+    )
+}
+
+// One time import of the json files and history file used before the database existed
+fn import_json(connection: &Connection, wallpapers_dir: &Path) -> Result<()> {
+    for path in json_paths(wallpapers_dir)? {
+        let content = fs::read_to_string(&path)?;
+        let mut wallpaper = match migration::decode_record(&content) {
+            Ok(wallpaper) => wallpaper,
+            Err(err) => {
+                eprintln!("Skipping {}: {err}", path.to_string_lossy());
+                continue;
+            }
+        };
+        if wallpaper.added_at.is_none() {
+            wallpaper.added_at = path
+                .metadata()
+                .and_then(|metadata| metadata.modified())
+                .ok()
+                .map(DateTime::<Utc>::from);
+        }
+        insert(connection, &wallpaper)?;
+    }
+
+    let history_path = wallpapers_dir.join("history");
+    if let Ok(content) = fs::read_to_string(history_path) {
+        if let Ok(history) = serde_json::from_str::<History>(&content) {
+            write_history(connection, DEFAULT_OUTPUT, &history)?;
+        }
+    }
+    for path in glob(&format!("{}/history.*", wallpapers_dir.to_str().unwrap()))?.flatten() {
+        let Some(output) = path.extension().and_then(|ext| ext.to_str()) else {
+            continue;
+        };
+        if let Ok(history) = serde_json::from_str::<History>(&fs::read_to_string(&path)?) {
+            write_history(connection, output, &history)?;
+        }
+    }
+    Ok(())
+}
+
+fn insert(connection: &Connection, wallpaper: &Wallpaper) -> Result<()> {
+    connection.execute(
+        "INSERT INTO wallpapers (md5, source, post_id, category, rating, score, width, height, added_at, data)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
+         ON CONFLICT (md5) DO UPDATE SET
+             source = excluded.source,
+             post_id = excluded.post_id,
+             category = excluded.category,
+             rating = excluded.rating,
+             score = excluded.score,
+             width = excluded.width,
+             height = excluded.height,
+             added_at = excluded.added_at,
+             data = excluded.data",
+        params![
+            wallpaper.md5,
+            wallpaper.source,
+            wallpaper.post_id,
+            wallpaper.category.as_ref().map(|category| category.to_string()),
+            wallpaper.rating.to_string(),
+            wallpaper.score,
+            wallpaper.original_width,
+            wallpaper.original_height,
+            wallpaper.added_at.unwrap_or_else(Utc::now).timestamp(),
+            migration::encode_record(wallpaper)?,
+        ],
+    )?;
+
+    connection.execute(
+        "DELETE FROM wallpaper_tags WHERE md5 = ?1",
+        [&wallpaper.md5],
+    )?;
+    for tag in wallpaper.tags.iter() {
+        connection.execute(
+            "INSERT OR IGNORE INTO wallpaper_tags (md5, tag) VALUES (?1, ?2)",
+            [&wallpaper.md5, tag],
+        )?;
+    }
+    Ok(())
+}
+
+fn history_idx_key(output: &str) -> String {
+    format!("history_idx:{output}")
+}
+
+fn write_history(connection: &Connection, output: &str, history: &History) -> Result<()> {
+    connection.execute("DELETE FROM history WHERE output = ?1", [output])?;
+    for (position, md5) in history.iter().enumerate() {
+        connection.execute(
+            "INSERT INTO history (output, position, md5) VALUES (?1, ?2, ?3)",
+            params![output, position, md5],
+        )?;
+    }
+    set_meta(
+        connection,
+        &history_idx_key(output),
+        &history.idx.to_string(),
+    )
+}
+
+fn read_history(connection: &Connection, output: &str) -> Result<Option<History>> {
+    let mut statement =
+        connection.prepare("SELECT md5 FROM history WHERE output = ?1 ORDER BY position")?;
+    let mut history = History::new();
+    for md5 in statement.query_map([output], |row| row.get(0))? {
+        history.push_back(md5?);
+    }
+    if history.is_empty() {
+        return Ok(None);
+    }
+    history.idx = get_meta(connection, &history_idx_key(output))?
+        .and_then(|idx| idx.parse().ok())
+        .unwrap_or(0)
+        .min(history.len().saturating_sub(1));
+    Ok(Some(history))
+}
+
+fn history_outputs(connection: &Connection) -> Result<Vec<String>> {
+    let mut statement =
+        connection.prepare("SELECT DISTINCT output FROM history ORDER BY output")?;
+    let outputs = statement
+        .query_map([], |row| row.get(0))?
+        .collect::<Result<_, _>>()?;
+    Ok(outputs)
+}
+
+// Histories this connection read or wrote, dropped once another connection commits a change
+#[derive(Default)]
+struct HistoryCache {
+    data_version: i64,
+    histories: HashMap<String, History>,
+}
+
+// One connection per command, clones share it with the tasks the command spawns
+#[derive(Clone)]
+pub struct Library {
+    dir: PathBuf,
+    connection: Arc<Mutex<Connection>>,
+    histories: Arc<Mutex<HistoryCache>>,
+}
+
+impl Library {
+    pub fn open(wallpapers_dir: &Path) -> Result<Library> {
+        Ok(Library {
+            dir: wallpapers_dir.to_path_buf(),
+            connection: Arc::new(Mutex::new(open(wallpapers_dir)?)),
+            histories: Default::default(),
+        })
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    fn connection(&self) -> MutexGuard<'_, Connection> {
+        self.connection.lock().unwrap()
+    }
+
+    pub fn save_wallpaper(&self, wallpaper: &Wallpaper) -> Result<()> {
+        let mut connection = self.connection();
+        let transaction = connection.transaction()?;
+        insert(&transaction, wallpaper)?;
+        transaction.commit()?;
+        Ok(())
+    }
+
+    pub fn mark_set(&self, md5: &str) -> Result<()> {
+        if let Some(mut wallpaper) = self.get_wallpaper(md5)? {
+            wallpaper.last_set_at = Some(Utc::now());
+            self.save_wallpaper(&wallpaper)?;
+        }
+        Ok(())
+    }
+
+    pub fn contains(&self, md5: &str) -> Result<bool> {
+        Ok(self
+            .connection()
+            .query_row("SELECT 1 FROM wallpapers WHERE md5 = ?1", [md5], |_| Ok(()))
+            .optional()?
+            .is_some())
+    }
+
+    pub fn get_wallpaper(&self, md5: &str) -> Result<Option<Wallpaper>> {
+        let data: Option<String> = self
+            .connection()
+            .query_row("SELECT data FROM wallpapers WHERE md5 = ?1", [md5], |row| {
+                row.get(0)
+            })
+            .optional()?;
+        data.as_deref().map(migration::decode_record).transpose()
+    }
+
+    fn select(&self, sql: &str, params: Vec<Value>) -> Result<Vec<Wallpaper>> {
+        let connection = self.connection();
+        let mut statement = connection.prepare(sql)?;
+        let rows = statement.query_map(params_from_iter(params), |row| row.get::<_, String>(0))?;
+
+        let mut wallpapers = vec![];
+        for data in rows {
+            wallpapers.push(migration::decode_record(&data?)?);
+        }
+        Ok(wallpapers)
+    }
+
+    pub fn wallpapers(&self) -> Result<Vec<Wallpaper>> {
+        self.find_wallpapers(&Query::default())
+    }
+
+    pub fn find_wallpapers(&self, query: &Query) -> Result<Vec<Wallpaper>> {
+        let mut params = vec![];
+        let filter = query.sql(&mut params);
+        self.select(
+            &format!("SELECT data FROM wallpapers WHERE {filter} ORDER BY md5"),
+            params,
+        )
+    }
+
+    // A random wallpaper matching the query that isn't blacklisted or excluded
+    pub fn random_wallpaper(
+        &self,
+        query: &Query,
+        blacklist: &Blacklist,
+        exclude: &[String],
+    ) -> Result<Option<Wallpaper>> {
+        let mut params = vec![];
+        let filter = query.sql(&mut params);
+        let blacklisted = blacklist.sql(&mut params);
+        let excluded = vec!["?"; exclude.len()].join(", ");
+        params.extend(exclude.iter().cloned().map(Value::from));
+        Ok(self
+            .select(
+                &format!(
+                    "SELECT data FROM wallpapers
+                     WHERE {filter} AND NOT ({blacklisted}) AND md5 NOT IN ({excluded})
+                     ORDER BY random() LIMIT 1"
+                ),
+                params,
+            )?
+            .pop())
+    }
+
+    pub fn known_posts(&self) -> Result<HashMap<(String, String), String>> {
+        let connection = self.connection();
+        let mut statement = connection
+            .prepare("SELECT source, post_id, md5 FROM wallpapers WHERE post_id IS NOT NULL")?;
+        let rows = statement.query_map([], |row| Ok(((row.get(0)?, row.get(1)?), row.get(2)?)))?;
+        Ok(rows.collect::<Result<_, _>>()?)
+    }
+
+    pub fn preferences(&self) -> Result<Preferences> {
+        let connection = self.connection();
+        let (mut liked, mut disliked): (usize, usize) = (0, 0);
+        let mut statement = connection.prepare(
+            "SELECT category, count(*) FROM wallpapers
+             WHERE category IS NOT NULL AND md5 IN (SELECT md5 FROM wallpaper_tags)
+             GROUP BY category",
+        )?;
+        for row in statement.query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, usize>(1)?))
+        })? {
+            match row? {
+                (category, count) if category == Category::Liked.to_string() => liked += count,
+                (_, count) => disliked += count,
+            }
+        }
+
+        let mut tags: HashMap<String, (usize, usize)> = HashMap::new();
+        let mut statement = connection.prepare(
+            "SELECT wallpaper_tags.tag, wallpapers.category, count(*) FROM wallpaper_tags
+             JOIN wallpapers ON wallpapers.md5 = wallpaper_tags.md5
+             WHERE wallpapers.category IS NOT NULL
+             GROUP BY wallpaper_tags.tag, wallpapers.category",
+        )?;
+        for row in statement.query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, usize>(2)?,
+            ))
+        })? {
+            let (tag, category, count) = row?;
+            let counts = tags.entry(tag).or_default();
+            if category == Category::Liked.to_string() {
+                counts.0 += count;
+            } else {
+                counts.1 += count;
+            }
+        }
+        Ok(Preferences::new(liked, disliked, tags))
+    }
+
+    // Outputs without a history of their own start from the one kept before outputs had names
+    // Cached, so a long running daemon doesn't read the history on every request
+    pub fn get_history(&self, output: &str) -> Result<History> {
+        let connection = self.connection();
+        // Changes only when another connection, like a review in another process, commits
+        let data_version = connection.query_row("PRAGMA data_version", [], |row| row.get(0))?;
+        let mut cache = self.histories.lock().unwrap();
+        if cache.data_version != data_version {
+            *cache = HistoryCache {
+                data_version,
+                ..Default::default()
+            };
+        }
+        if let Some(history) = cache.histories.get(output) {
+            return Ok(history.clone());
+        }
+
+        let history = match read_history(&connection, output)? {
+            Some(history) => history,
+            None => read_history(&connection, DEFAULT_OUTPUT)?.unwrap_or_else(History::new),
+        };
+        cache.histories.insert(output.to_owned(), history.clone());
+        Ok(history)
+    }
+
+    pub fn save_history(&self, output: &str, history: &History) -> Result<()> {
+        let mut connection = self.connection();
+        let transaction = connection.transaction()?;
+        write_history(&transaction, output, history)?;
+        transaction.commit()?;
+        self.histories
+            .lock()
+            .unwrap()
+            .histories
+            .insert(output.to_owned(), history.clone());
+        Ok(())
+    }
+
+    pub fn export(&self, export_dir: &Path) -> Result<usize> {
+        fs::create_dir_all(export_dir)?;
+        let wallpapers = self.wallpapers()?;
+        for wallpaper in wallpapers.iter() {
+            fs::write(
+                export_dir.join(&wallpaper.md5).with_extension("json"),
+                migration::encode_record(wallpaper)?.as_bytes(),
+            )?;
+        }
+        let connection = self.connection();
+        for output in history_outputs(&connection)? {
+            let Some(history) = read_history(&connection, &output)? else {
+                continue;
+            };
+            let file_name = match output.as_str() {
+                DEFAULT_OUTPUT => "history".to_owned(),
+                output => format!("history.{output}"),
+            };
+            fs::write(
+                export_dir.join(file_name),
+                serde_json::to_string(&history)?.as_bytes(),
+            )?;
+        }
+        Ok(wallpapers.len())
+    }
+}
+
+pub struct Migrated {
+    pub backup_path: PathBuf,
+    pub from_version: u32,
+    pub records: usize,
+}
+
+pub fn migrate(wallpapers_dir: &Path) -> Result<Option<Migrated>> {
+    let database_path = wallpapers_dir.join(DATABASE_FILE);
+    if !database_path.exists() {
+        open(wallpapers_dir)?;
+        return Ok(None);
+    }
+
+    let mut connection = Connection::open(&database_path)?;
+    let from_version = migration::schema_version(&connection)?;
+    let outdated: usize = connection.query_row(
+        "SELECT count(*) FROM wallpapers WHERE json_extract(data, '$.version') IS NOT ?1",
+        [migration::RECORD_VERSION],
+        |row| row.get(0),
+    )?;
+    if from_version == migration::SCHEMA_VERSION && outdated == 0 {
+        return Ok(None);
+    }
+
+    let backup_path = wallpapers_dir.join(format!(
+        "{DATABASE_FILE}.{}.bak",
+        Utc::now().format("%Y%m%d%H%M%S")
+    ));
+    fs::copy(&database_path, &backup_path)?;
+
+    let transaction = connection.transaction()?;
+    migration::migrate_schema(&transaction)?;
+    let records: Vec<String> = transaction
+        .prepare("SELECT data FROM wallpapers WHERE json_extract(data, '$.version') IS NOT ?1")?
+        .query_map([migration::RECORD_VERSION], |row| row.get(0))?
+        .collect::<Result<_, _>>()?;
+    // Saved whole so the indexed columns and tags follow whatever the migration changed
+    for data in records.iter() {
+        insert(&transaction, &migration::decode_record(data)?)?;
+    }
+    transaction.commit()?;
+
+    Ok(Some(Migrated {
+        backup_path,
+        from_version,
+        records: records.len(),
+    }))
+}
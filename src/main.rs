@@ -1,32 +1,48 @@
 #![feature(try_blocks)]
 #![feature(exit_status_error)]
-#![feature(linked_list_cursors)]
 #![feature(async_closure)]
-#![feature(error_generic_member_access)]
-
-use std::process::{Command, Stdio};
 
 use crate::cli::Cli;
-use crate::commands::{download::download, get::get, review::review, set::set, Commands};
+use crate::commands::set::targets;
+use crate::commands::{
+    daemon::daemon, download::download, export::export, fix_extensions::fix_extensions, get::get,
+    import::import, list::list, migrate::migrate, review::review, set::set, verify::verify,
+    Commands,
+};
 use anyhow::Result;
 use config::get_config;
 
-use crate::wallpaper_history::get_history;
+use crate::download::DownloadLimits;
+use crate::library::Library;
+use crate::output::{get_outputs, select_output};
+use crate::process::ReviewSession;
 
 use clap::Parser;
 use directories::{ProjectDirs, UserDirs};
+use std::num::NonZeroUsize;
 
 use tokio::fs;
 
 use tokio_util::sync::CancellationToken;
 
+mod blacklist;
 mod cli;
 mod commands;
 mod config;
 mod crop;
+mod daemon;
 mod download;
+mod image_format;
+mod library;
+mod migration;
+mod order;
+mod output;
+mod preference;
 mod process;
+mod query;
 mod rating;
+mod setter;
+mod source;
 mod wallpaper;
 mod wallpaper_history;
 mod wallpaper_list;
@@ -58,6 +74,11 @@ async fn main() -> Result<()> {
         fs::create_dir_all(&wallpapers_dir).await?;
     }
 
+    // Everything below opens the library, which refuses to work with an outdated one
+    if let Commands::Migrate = args.command {
+        return migrate(wallpapers_dir);
+    }
+
     let config_dir = args
         .config_dir
         .or_else(|| project_dirs.map(|pd| pd.config_dir().to_path_buf()))
@@ -67,50 +88,34 @@ async fn main() -> Result<()> {
         fs::create_dir_all(&config_dir).await?;
     }
 
-    let config = get_config(&config_dir)?;
+    // A daemon serving this library and config already knows the outputs, set and get run in it
+    // entirely unless it doesn't answer
+    let daemon_outputs = match args.command {
+        Commands::Daemon { .. } => None,
+        _ => daemon::connect(&wallpapers_dir, &config_dir),
+    };
+    if daemon_outputs.is_some()
+        && matches!(args.command, Commands::Set { .. } | Commands::Get { .. })
+    {
+        if let Some(result) = daemon::forward(&wallpapers_dir, &config_dir) {
+            return result;
+        }
+    }
 
-    let width: u16 = args
-        .screen_width
-        .or_else(|| {
-            let command = config.get_screen_width_command.clone();
-
-            let words = shell_words::split(&command).ok()?;
-            let (command_name, command_args) = words.split_first()?;
-            let child = Command::new(command_name)
-                .args(command_args)
-                .stdout(Stdio::piped())
-                .spawn()
-                .ok()?;
-
-            String::from_utf8(child.wait_with_output().ok()?.stdout)
-                .ok()?
-                .trim()
-                .parse::<u16>()
-                .ok()
-        })
-        .expect("Invalid get screen width command, check your config");
+    let config = get_config(&config_dir)?;
 
-    let height: u16 = args
-        .screen_height
-        .or_else(|| {
-            let command = config.get_screen_height_command.clone();
-
-            let words = shell_words::split(&command).ok()?;
-            let (command_name, command_args) = words.split_first()?;
-            let child = Command::new(command_name)
-                .args(command_args)
-                .stdout(Stdio::piped())
-                .spawn()
-                .ok()?;
-
-            String::from_utf8(child.wait_with_output().ok()?.stdout)
-                .ok()?
-                .trim()
-                .parse::<u16>()
-                .ok()
-        })
-        .expect("Invalid get screen height command, check your config");
-    let history = get_history(&wallpapers_dir)?;
+    let outputs = match daemon_outputs {
+        Some(outputs) => outputs,
+        None => get_outputs(&config, args.screen_width, args.screen_height)?,
+    };
+    let output = select_output(
+        &outputs,
+        &args.output,
+        args.screen_width,
+        args.screen_height,
+    )?;
+    let library = Library::open(&wallpapers_dir)?;
+    let history = library.get_history(&output.name)?;
 
     let token = CancellationToken::new();
     let token_cloned = token.clone();
@@ -129,49 +134,96 @@ async fn main() -> Result<()> {
 
     match &args.command {
         Commands::Download {
-            download_width,
-            download_height,
-            tags,
-            rating,
+            source,
+            filters,
+            jobs,
+            no_review,
+            limit,
+            max_bytes,
         } => {
-            let wallpapers_dir = wallpapers_dir.clone();
             download(
-                download_width,
-                download_height,
-                width,
-                height,
-                tags,
-                rating,
-                wallpapers_dir,
-                config_dir,
+                source,
+                filters,
+                *jobs,
+                *no_review,
+                DownloadLimits {
+                    count: limit.map(NonZeroUsize::get),
+                    bytes: *max_bytes,
+                },
                 cache_dir,
-                history,
-                token_cloned,
-                args.set_wallpaper_command,
+                ReviewSession {
+                    output,
+                    library,
+                    config,
+                    history,
+                    token: token_cloned,
+                    set_wallpaper_command_override: args.set_wallpaper_command,
+                },
             )
             .await?
         }
-        Commands::Set { subcommand } => set(
+        Commands::Set {
             subcommand,
-            wallpapers_dir,
-            config_dir,
-            history,
-            args.set_wallpaper_command,
-        )?,
-        Commands::Get { subcommand } => get(subcommand, wallpapers_dir, config_dir, history)?,
-        Commands::Review { subcommand } => {
-            review(
-                width,
-                height,
+            all_outputs,
+        } => {
+            set(
                 subcommand,
-                wallpapers_dir,
-                config_dir,
-                history,
-                token_cloned,
+                &targets(
+                    subcommand,
+                    *all_outputs,
+                    &outputs,
+                    output,
+                    args.screen_width,
+                    args.screen_height,
+                )?,
+                &library,
+                &config,
                 args.set_wallpaper_command,
             )
             .await?
         }
+        Commands::Get { subcommand } => {
+            let json = get(subcommand, &library, config_dir, history)?;
+            if !json.is_empty() {
+                println!("{json}");
+            }
+        }
+        Commands::Review { subcommand, order } => {
+            review(
+                subcommand,
+                order,
+                ReviewSession {
+                    output,
+                    library,
+                    config,
+                    history,
+                    token: token_cloned,
+                    set_wallpaper_command_override: args.set_wallpaper_command,
+                },
+            )
+            .await?
+        }
+        Commands::Import {
+            dir,
+            recursive,
+            link,
+            rating,
+        } => import(dir, *recursive, *link, rating, &library)?,
+        Commands::Verify => verify(&library)?,
+        Commands::FixExtensions => fix_extensions(&library)?,
+        Commands::Export { dir } => export(dir, &library)?,
+        Commands::Migrate => unreachable!("migrate returns before the library is opened"),
+        Commands::Daemon { rotate } => {
+            daemon(outputs, *rotate, library, config, config_dir, token_cloned).await?
+        }
+        Commands::List {
+            query,
+            format,
+            sort,
+            reverse,
+            limit,
+            fields,
+        } => list(query, format, sort, *reverse, *limit, fields, &library)?,
     };
     Ok(())
 }
@@ -0,0 +1,162 @@
+use anyhow::Result;
+use rusqlite::Connection;
+use serde_json::{Map, Value};
+
+use crate::wallpaper::Wallpaper;
+
+pub const RECORD_VERSION: u64 = 1;
+pub const SCHEMA_VERSION: u32 = 1;
+
+type RecordMigration = fn(&mut Map<String, Value>);
+
+// RECORD_MIGRATIONS[n] upgrades a record from version n to n + 1, records without a version are 0
+const RECORD_MIGRATIONS: [RecordMigration; RECORD_VERSION as usize] = [record_v1];
+
+// SCHEMA_MIGRATIONS[n] upgrades the database from user_version n to n + 1
+const SCHEMA_MIGRATIONS: [&str; SCHEMA_VERSION as usize] = [
+    // 1: records carry their own version, the tables stay the same
+    "",
+];
+
+#[derive(Debug, thiserror::Error)]
+pub enum MigrationError {
+    #[error("Record is not a json object")]
+    NotAnObject,
+    #[error("Record version {0} is newer than {RECORD_VERSION}, upgrade aniwall")]
+    NewerRecord(u64),
+    #[error("Library version {0} is newer than {SCHEMA_VERSION}, upgrade aniwall")]
+    NewerSchema(u32),
+    #[error("Library version {0} is older than {SCHEMA_VERSION}, run `aniwall migrate` first")]
+    OutdatedSchema(u32),
+}
+
+// Konachan was the only source before records had a source and post id, and nothing else about
+// the post was kept
+fn record_v1(record: &mut Map<String, Value>) {
+    record.entry("source").or_insert_with(|| "konachan".into());
+    record.entry("tags").or_insert_with(|| Value::Array(vec![]));
+    for field in [
+        "post_id",
+        "artist",
+        "uploader",
+        "source_url",
+        "post_url",
+        "created_at",
+        "added_at",
+        "reviewed_at",
+        "last_set_at",
+    ] {
+        record.entry(field).or_insert(Value::Null);
+    }
+}
+
+fn record_version(record: &Map<String, Value>) -> u64 {
+    record.get("version").and_then(Value::as_u64).unwrap_or(0)
+}
+
+pub fn decode_record(data: &str) -> Result<Wallpaper> {
+    let Value::Object(mut record) = serde_json::from_str(data)? else {
+        Err(MigrationError::NotAnObject)?
+    };
+    let version = record_version(&record);
+    if version > RECORD_VERSION {
+        Err(MigrationError::NewerRecord(version))?
+    }
+    for migration in &RECORD_MIGRATIONS[version as usize..] {
+        migration(&mut record);
+    }
+    record.remove("version");
+    Ok(serde_json::from_value(Value::Object(record))?)
+}
+
+pub fn encode_record(wallpaper: &Wallpaper) -> Result<String> {
+    let Value::Object(mut record) = serde_json::to_value(wallpaper)? else {
+        Err(MigrationError::NotAnObject)?
+    };
+    record.insert("version".to_owned(), RECORD_VERSION.into());
+    Ok(serde_json::to_string(&record)?)
+}
+
+pub fn schema_version(connection: &Connection) -> Result<u32> {
+    Ok(connection.query_row("PRAGMA user_version", [], |row| row.get(0))?)
+}
+
+pub fn set_schema_version(connection: &Connection, version: u32) -> Result<()> {
+    connection.pragma_update(None, "user_version", version)?;
+    Ok(())
+}
+
+pub fn check_schema(connection: &Connection) -> Result<()> {
+    match schema_version(connection)? {
+        SCHEMA_VERSION => Ok(()),
+        version if version > SCHEMA_VERSION => Err(MigrationError::NewerSchema(version))?,
+        version => Err(MigrationError::OutdatedSchema(version))?,
+    }
+}
+
+pub fn migrate_schema(connection: &Connection) -> Result<u32> {
+    let version = schema_version(connection)?;
+    if version > SCHEMA_VERSION {
+        Err(MigrationError::NewerSchema(version))?
+    }
+    for migration in &SCHEMA_MIGRATIONS[version as usize..] {
+        connection.execute_batch(migration)?;
+    }
+    set_schema_version(connection, SCHEMA_VERSION)?;
+    Ok(version)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A record as written before records had a version, source or post id
+    const BASELINE_RECORD: &str = r#"{
+        "md5": "0123456789abcdef0123456789abcdef",
+        "original_url": "https://konachan.com/image/0123456789abcdef0123456789abcdef.png",
+        "original_width": 1920,
+        "original_height": 1080,
+        "score": 10,
+        "rating": "Safe",
+        "prefered": "Cropped",
+        "category": "Liked",
+        "downloaded_image_path": "/wallpapers/0123456789abcdef0123456789abcdef.png",
+        "crop_data": {
+            "cropped_image_path": "/wallpapers/cropped/0123456789abcdef0123456789abcdef.png",
+            "crop_offset_x": 240,
+            "crop_offset_y": 0
+        }
+    }"#;
+
+    #[test]
+    fn upgrades_baseline_records() {
+        let wallpaper = decode_record(BASELINE_RECORD).unwrap();
+        assert_eq!(wallpaper.source, "konachan");
+        assert_eq!(wallpaper.post_id, None);
+        assert!(wallpaper.tags.is_empty());
+        assert_eq!((wallpaper.artist, wallpaper.uploader), (None, None));
+        assert_eq!(wallpaper.added_at, None);
+        assert_eq!(wallpaper.crop_data.unwrap().crop_offset_x, 240);
+    }
+
+    #[test]
+    fn round_trips_current_records() {
+        let mut wallpaper = decode_record(BASELINE_RECORD).unwrap();
+        wallpaper.source = "danbooru".to_owned();
+        wallpaper.post_id = Some("7".to_owned());
+        wallpaper.tags = vec!["scenery".to_owned()];
+        let encoded = encode_record(&wallpaper).unwrap();
+        assert!(encoded.contains(&format!("\"version\":{RECORD_VERSION}")));
+        assert_eq!(decode_record(&encoded).unwrap(), wallpaper);
+    }
+
+    #[test]
+    fn rejects_unknown_records() {
+        let Value::Object(mut record) = serde_json::from_str(BASELINE_RECORD).unwrap() else {
+            unreachable!()
+        };
+        record.insert("version".to_owned(), (RECORD_VERSION + 1).into());
+        assert!(decode_record(&Value::Object(record).to_string()).is_err());
+        assert!(decode_record("[]").is_err());
+    }
+}
@@ -0,0 +1,184 @@
+use std::{
+    cmp::{Ordering, Reverse},
+    fmt,
+    str::FromStr,
+};
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+
+use crate::{library::Library, preference::Preferences, source::Post, wallpaper::Wallpaper};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Order {
+    ScoreDesc,
+    ScoreAsc,
+    Newest,
+    Oldest,
+    Random(Option<u64>),
+    Predicted,
+    AspectRatio,
+    Resolution,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum OrderParseError {
+    VariantNotFound,
+    InvalidSeed(#[from] std::num::ParseIntError),
+}
+
+impl fmt::Display for OrderParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            OrderParseError::VariantNotFound => write!(
+                f,
+                "Matching variant not found, must be one of: score-desc|score, score-asc, newest, oldest, random, random:<seed>, predicted, aspect-ratio, resolution"
+            ),
+            OrderParseError::InvalidSeed(err) => write!(f, "Invalid random seed, {err}"),
+        }
+    }
+}
+
+impl FromStr for Order {
+    type Err = OrderParseError;
+
+    fn from_str(input: &str) -> Result<Order, Self::Err> {
+        match input {
+            "score-desc" | "score" => Ok(Order::ScoreDesc),
+            "score-asc" => Ok(Order::ScoreAsc),
+            "newest" => Ok(Order::Newest),
+            "oldest" => Ok(Order::Oldest),
+            "random" => Ok(Order::Random(None)),
+            "predicted" => Ok(Order::Predicted),
+            "aspect-ratio" | "ratio" => Ok(Order::AspectRatio),
+            "resolution" => Ok(Order::Resolution),
+            input => match input.strip_prefix("random:") {
+                Some(seed) => Ok(Order::Random(Some(seed.parse()?))),
+                None => Err(OrderParseError::VariantNotFound),
+            },
+        }
+    }
+}
+
+impl fmt::Display for Order {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Order::ScoreDesc => write!(f, "score-desc"),
+            Order::ScoreAsc => write!(f, "score-asc"),
+            Order::Newest => write!(f, "newest"),
+            Order::Oldest => write!(f, "oldest"),
+            Order::Random(None) => write!(f, "random"),
+            Order::Random(Some(seed)) => write!(f, "random:{seed}"),
+            Order::Predicted => write!(f, "predicted"),
+            Order::AspectRatio => write!(f, "aspect-ratio"),
+            Order::Resolution => write!(f, "resolution"),
+        }
+    }
+}
+
+pub trait Rankable {
+    fn score(&self) -> i32;
+    fn width(&self) -> i32;
+    fn height(&self) -> i32;
+    fn tags(&self) -> &[String];
+    fn date(&self) -> Option<DateTime<Utc>>;
+}
+
+impl Rankable for Post {
+    fn score(&self) -> i32 {
+        self.score
+    }
+    fn width(&self) -> i32 {
+        self.width
+    }
+    fn height(&self) -> i32 {
+        self.height
+    }
+    fn tags(&self) -> &[String] {
+        &self.tags
+    }
+    fn date(&self) -> Option<DateTime<Utc>> {
+        self.created_at
+    }
+}
+
+impl Rankable for Wallpaper {
+    fn score(&self) -> i32 {
+        self.score
+    }
+    fn width(&self) -> i32 {
+        self.original_width
+    }
+    fn height(&self) -> i32 {
+        self.original_height
+    }
+    fn tags(&self) -> &[String] {
+        &self.tags
+    }
+    fn date(&self) -> Option<DateTime<Utc>> {
+        self.created_at.or(self.added_at)
+    }
+}
+
+// How far the aspect ratio is from the screen's, on a log scale so 2:1 and 1:2 are equally far
+// from 1:1
+fn ratio_distance<T: Rankable>(item: &T, screen_ratio: f64) -> f64 {
+    if item.height() <= 0 {
+        return f64::INFINITY;
+    }
+    (item.width() as f64 / item.height() as f64 / screen_ratio)
+        .ln()
+        .abs()
+}
+
+// f64 ordered by total_cmp so it can be a sort key
+struct TotalOrder(f64);
+
+impl PartialEq for TotalOrder {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for TotalOrder {}
+
+impl PartialOrd for TotalOrder {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for TotalOrder {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+
+impl Order {
+    // Only the predicted order uses the model, building it reads every categorized wallpaper
+    pub fn preferences(&self, library: &Library) -> Result<Preferences> {
+        match self {
+            Order::Predicted => library.preferences(),
+            _ => Ok(Preferences::default()),
+        }
+    }
+
+    pub fn sort<T: Rankable>(&self, items: &mut [T], preferences: &Preferences, screen_ratio: f64) {
+        match self {
+            Order::ScoreDesc => items.sort_by_key(|item| Reverse(item.score())),
+            Order::ScoreAsc => items.sort_by_key(|item| item.score()),
+            Order::Newest => items.sort_by_key(|item| Reverse(item.date())),
+            Order::Oldest => items.sort_by_key(|item| item.date()),
+            Order::Random(None) => items.shuffle(&mut rand::thread_rng()),
+            Order::Random(Some(seed)) => items.shuffle(&mut StdRng::seed_from_u64(*seed)),
+            Order::Predicted => items
+                .sort_by_cached_key(|item| Reverse(TotalOrder(preferences.predict(item.tags())))),
+            Order::AspectRatio => items.sort_by(|a, b| {
+                ratio_distance(a, screen_ratio).total_cmp(&ratio_distance(b, screen_ratio))
+            }),
+            Order::Resolution => items
+                .sort_by_key(|item| Reverse(i64::from(item.width()) * i64::from(item.height()))),
+        }
+    }
+}
@@ -0,0 +1,206 @@
+use std::process::{Command, Stdio};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::config::Config;
+
+// Name of the single output used when outputs can't be listed, swaybg reads it as every output
+pub const DEFAULT_OUTPUT: &str = "*";
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Output {
+    pub name: String,
+    pub width: u16,
+    pub height: u16,
+    pub x: i32,
+    pub y: i32,
+    // The position is in logical pixels, the resolution divided by this
+    #[serde(default = "default_scale")]
+    pub scale: f64,
+    // Picked by --output, or one of several with --all-outputs or span, otherwise it stands for the
+    // whole desktop to setters that can only set every output
+    #[serde(skip)]
+    pub targeted: bool,
+}
+
+fn default_scale() -> f64 {
+    1.0
+}
+
+impl Output {
+    pub fn ratio(&self) -> f64 {
+        f64::from(self.width) / f64::from(self.height)
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum OutputError {
+    #[error("Could not find the screen size, check get_outputs_command in your config or pass --screen-width and --screen-height")]
+    NoOutputs,
+    #[error("Output {0} not found, must be one of: {1}")]
+    NotFound(String, String),
+    #[error("--screen-width and --screen-height set the size of one output, they can't be used with --all-outputs or span when there are several")]
+    ScreenSizeForAllOutputs,
+}
+
+fn run(command: &str) -> Option<String> {
+    let words = shell_words::split(command).ok()?;
+    let (command_name, command_args) = words.split_first()?;
+    let child = Command::new(command_name)
+        .args(command_args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .ok()?;
+
+    String::from_utf8(child.wait_with_output().ok()?.stdout).ok()
+}
+
+// One output per line, the name, resolution and optionally the position and scale like
+// `DP-1 2560x1440 1920x0 1.5`
+fn parse_outputs(output: &str) -> Vec<Output> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let name = fields.next()?;
+            let (width, height) = fields.next()?.split_once('x')?;
+            let (x, y) = fields.next().unwrap_or("0x0").split_once('x')?;
+            let scale: f64 = fields.next().unwrap_or("1").parse().ok()?;
+            Some(Output {
+                name: name.to_owned(),
+                width: width.parse().ok()?,
+                height: height.parse().ok()?,
+                x: x.parse().ok()?,
+                y: y.parse().ok()?,
+                scale: (scale > 0.0).then_some(scale)?,
+                targeted: false,
+            })
+        })
+        .collect()
+}
+
+fn default_output(config: &Config) -> Option<Output> {
+    let width = run(&config.get_screen_width_command)?.trim().parse().ok()?;
+    let height = run(&config.get_screen_height_command)?
+        .trim()
+        .parse()
+        .ok()?;
+    Some(Output {
+        name: DEFAULT_OUTPUT.to_owned(),
+        width,
+        height,
+        x: 0,
+        y: 0,
+        scale: 1.0,
+        targeted: false,
+    })
+}
+
+pub fn get_outputs(
+    config: &Config,
+    screen_width: Option<u16>,
+    screen_height: Option<u16>,
+) -> Result<Vec<Output>> {
+    let mut outputs = run(&config.get_outputs_command)
+        .map(|output| parse_outputs(&output))
+        .unwrap_or_default();
+
+    if outputs.is_empty() {
+        match (screen_width, screen_height) {
+            (Some(width), Some(height)) => outputs.push(Output {
+                name: DEFAULT_OUTPUT.to_owned(),
+                width,
+                height,
+                x: 0,
+                y: 0,
+                scale: 1.0,
+                targeted: false,
+            }),
+            _ => outputs.extend(default_output(config)),
+        }
+    }
+    if outputs.is_empty() {
+        Err(OutputError::NoOutputs)?
+    }
+    Ok(outputs)
+}
+
+// The named output or the first one, with --screen-width and --screen-height overriding its size
+pub fn select_output(
+    outputs: &[Output],
+    name: &Option<String>,
+    screen_width: Option<u16>,
+    screen_height: Option<u16>,
+) -> Result<Output> {
+    let mut output = match name {
+        Some(name) => outputs
+            .iter()
+            .find(|output| output.name == *name)
+            .ok_or_else(|| {
+                OutputError::NotFound(
+                    name.to_owned(),
+                    outputs
+                        .iter()
+                        .map(|output| output.name.as_str())
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                )
+            })?,
+        None => &outputs[0],
+    }
+    .clone();
+    output.width = screen_width.unwrap_or(output.width);
+    output.height = screen_height.unwrap_or(output.height);
+    output.targeted = name.is_some();
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_outputs() {
+        let outputs = parse_outputs("DP-1 2560x1440 0x0\nHDMI-A-1 1920x1080 2560x-200\n\nbroken\n");
+        assert_eq!(
+            outputs
+                .iter()
+                .map(|output| (
+                    output.name.as_str(),
+                    output.width,
+                    output.height,
+                    output.x,
+                    output.y
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                ("DP-1", 2560, 1440, 0, 0),
+                ("HDMI-A-1", 1920, 1080, 2560, -200)
+            ]
+        );
+    }
+
+    #[test]
+    fn position_is_optional() {
+        let outputs = parse_outputs("eDP-1 1920x1200");
+        assert_eq!((outputs[0].x, outputs[0].y), (0, 0));
+        assert!(parse_outputs("eDP-1 wide").is_empty());
+    }
+
+    #[test]
+    fn parses_scale() {
+        let outputs = parse_outputs("DP-1 2560x1440 0x0 1.50\nDP-2 1920x1080 1706x0");
+        assert_eq!(
+            outputs
+                .iter()
+                .map(|output| output.scale)
+                .collect::<Vec<_>>(),
+            vec![1.5, 1.0]
+        );
+        assert!(parse_outputs("DP-1 2560x1440 0x0 0").is_empty());
+        assert!(parse_outputs("DP-1 2560x1440 0x0 big").is_empty());
+    }
+}
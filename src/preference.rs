@@ -0,0 +1,36 @@
+use std::collections::HashMap;
+
+// Naive Bayes over tags, trained on Liked wallpapers against Disliked and Borked ones
+#[derive(Debug, Default)]
+pub struct Preferences {
+    liked: usize,
+    disliked: usize,
+    tags: HashMap<String, (usize, usize)>,
+}
+
+impl Preferences {
+    pub fn new(liked: usize, disliked: usize, tags: HashMap<String, (usize, usize)>) -> Self {
+        Preferences {
+            liked,
+            disliked,
+            tags,
+        }
+    }
+
+    // Log odds of liking a post with these tags, tags never seen in a categorized wallpaper
+    // are ignored
+    pub fn predict(&self, tags: &[String]) -> f64 {
+        let liked = self.liked as f64;
+        let disliked = self.disliked as f64;
+        let prior = ((liked + 1.0) / (disliked + 1.0)).ln();
+
+        tags.iter().filter_map(|tag| self.tags.get(tag)).fold(
+            prior,
+            |odds, (tag_liked, tag_disliked)| {
+                let p_liked = (*tag_liked as f64 + 1.0) / (liked + 2.0);
+                let p_disliked = (*tag_disliked as f64 + 1.0) / (disliked + 2.0);
+                odds + (p_liked / p_disliked).ln()
+            },
+        )
+    }
+}
@@ -1,14 +1,18 @@
+use crate::config::Config;
 use crate::crop::crop_wallpaper;
+use crate::library::Library;
+use crate::output::Output;
 use crate::rating::Category;
 use crate::wallpaper::{set_wallpaper, Prefered, Wallpaper};
-use crate::wallpaper_history::{save_history, History};
+use crate::wallpaper_history::History;
 
 use crate::rating::CategoryPrompt;
 use anyhow::Result;
+use chrono::Utc;
 use inquire::{InquireError, Select};
-use std::{path::PathBuf, sync::Arc};
+use std::sync::Arc;
 use tokio::{
-    fs, select,
+    select,
     sync::{
         mpsc::{self, Receiver, Sender, UnboundedReceiver, UnboundedSender},
         Mutex,
@@ -17,6 +21,16 @@ use tokio::{
 };
 use tokio_util::sync::CancellationToken;
 
+// Where and how reviewed wallpapers are shown and saved, shared by review and download
+pub struct ReviewSession {
+    pub output: Output,
+    pub library: Library,
+    pub config: Config,
+    pub history: History,
+    pub token: CancellationToken,
+    pub set_wallpaper_command_override: Option<String>,
+}
+
 pub enum ProcessingStatus {
     Interupted,
     ToogleCropped,
@@ -72,14 +86,16 @@ pub async fn process_wallpaper(
 
 pub async fn process_wallpapers(
     mut wallpapers_rx: Receiver<Wallpaper>,
-    token: CancellationToken,
-    history: History,
-    wallpapers_dir: PathBuf,
-    config_dir: PathBuf,
-    screen_width: u16,
-    screen_height: u16,
-    set_wallpaper_command_override: Option<String>,
+    session: ReviewSession,
 ) -> Result<()> {
+    let ReviewSession {
+        output,
+        library,
+        config,
+        history,
+        token,
+        set_wallpaper_command_override,
+    } = session;
     let (cropped_tx, mut cropped_rx): (Sender<Wallpaper>, Receiver<Wallpaper>) = mpsc::channel(10);
     let (process_tx, mut process_rx): (Sender<Wallpaper>, Receiver<Wallpaper>) = mpsc::channel(10);
     let (shutdown_tx, mut shutdown_rx): (UnboundedSender<()>, UnboundedReceiver<()>) =
@@ -93,27 +109,28 @@ pub async fn process_wallpapers(
         let token_cloned = token.clone();
         let history = history.clone();
         let prompt = prompt.clone();
-        let config_dir = config_dir.clone();
-        let wallpapers_dir = wallpapers_dir.clone();
+        let config = config.clone();
+        let library = library.clone();
         let set_wallpaper_command_override = set_wallpaper_command_override.clone();
+        let output = output.clone();
 
         async move {
             let _ = shutdown_tx2.clone();
             select! {
                 biased;
                 _ = token_cloned.cancelled() => {}
-                _ = async move || -> Result<()> {
+                _ = async move {
                     'outer: while let Some(mut wallpaper) = process_rx.recv().await {
                         let prompt_lock = prompt.lock().await;
                         let mut is_cropped = wallpaper.prefered == Prefered::Cropped;
                         loop {
                             if is_cropped {
                                 if let Some(crop_data) = &wallpaper.crop_data {
-                                    set_wallpaper(&config_dir, &crop_data.cropped_image_path, set_wallpaper_command_override.clone())?;
+                                    set_wallpaper(&config, &crop_data.cropped_image_path, &output.name, output.targeted, set_wallpaper_command_override.clone()).await?;
                                 }
                                 wallpaper.prefered = Prefered::Cropped;
                             } else {
-                                set_wallpaper(&config_dir, &wallpaper.downloaded_image_path, set_wallpaper_command_override.clone())?;
+                                set_wallpaper(&config, &wallpaper.downloaded_image_path, &output.name, output.targeted, set_wallpaper_command_override.clone()).await?;
                                 wallpaper.prefered = Prefered::Original;
                             }
 
@@ -126,10 +143,10 @@ pub async fn process_wallpapers(
                                 ProcessingStatus::Interupted => {
                                     let mut history = history.lock().await;
                                     if let Some(md5) = history.prev() {
-                                        Wallpaper::from_md5(&wallpapers_dir, &md5)
+                                        Wallpaper::from_md5(&library, &md5)
                                             ?
-                                            .set_prefered(&config_dir, set_wallpaper_command_override)
-                                            ?;
+                                            .set_prefered(&config, &output, set_wallpaper_command_override)
+                                            .await?;
                                     }
                                     token.cancel();
                                     break 'outer;
@@ -138,18 +155,16 @@ pub async fn process_wallpapers(
                                     is_cropped = !is_cropped;
                                 }
                                 ProcessingStatus::Done => {
-                                    let path = wallpapers_dir.join(&wallpaper.md5).with_extension("json");
-                                    let json = serde_json::to_string(&wallpaper)?;
-
-                                    fs::write(path, json.as_bytes()).await?;
+                                    wallpaper.reviewed_at = Some(Utc::now());
+                                    library.save_wallpaper(&wallpaper)?;
                                     break;
                                 }
                             }
                         }
                         drop(prompt_lock);
                     }
-                    Ok(())
-                }() => {}
+                    anyhow::Ok(())
+                } => {}
             }
         }
     });
@@ -157,6 +172,7 @@ pub async fn process_wallpapers(
     tokio::task::spawn({
         let token = token.clone();
         let history_clone = history.clone();
+        let output = output.clone();
 
         async move {
             let _ = shutdown_tx.clone();
@@ -168,8 +184,8 @@ pub async fn process_wallpapers(
                         while let Some(original) = cropped_rx.recv().await {
                             let wallpaper = crop_wallpaper(
                                 original,
-                                screen_width,
-                                screen_height,
+                                output.width,
+                                output.height,
                             )
                             .await?;
 
@@ -193,13 +209,14 @@ pub async fn process_wallpapers(
         biased;
         _ = token_cloned.cancelled() => {}
         _ = {
-                let wallpapers_dir = wallpapers_dir.clone();
-            async move || -> Result<()> {
+                let library = library.clone();
+                let output = output.clone();
+            async move {
 
                 while let Some(mut wallpaper) = wallpapers_rx.recv().await {
 
                     let prompt_lock = prompt.lock().await;
-                    let is_cropped = wallpaper.set_prefered(&config_dir, set_wallpaper_command_override.clone())? == Prefered::Cropped;
+                    let is_cropped = wallpaper.set_prefered(&config, &output, set_wallpaper_command_override.clone()).await? == Prefered::Cropped;
 
                     let mut history = history_clone.lock().await;
                     history.push(wallpaper.md5.clone());
@@ -214,10 +231,10 @@ pub async fn process_wallpapers(
                         ProcessingStatus::Interupted => {
                             let mut history = history_clone.lock().await;
                             if let Some(md5) = history.prev() {
-                                Wallpaper::from_md5(&wallpapers_dir, &md5)
+                                Wallpaper::from_md5(&library, &md5)
                                     ?
-                                    .set_prefered(&config_dir, set_wallpaper_command_override)
-                                    ?;
+                                    .set_prefered(&config, &output, set_wallpaper_command_override)
+                                    .await?;
                             }
                             token.cancel();
                             break;
@@ -226,22 +243,19 @@ pub async fn process_wallpapers(
                             cropped_tx.send(wallpaper).await?;
                         }
                         ProcessingStatus::Done => {
-                            let path = wallpapers_dir.join(&wallpaper.md5).with_extension("json");
-                            let json = serde_json::to_string(&wallpaper)?;
-
-                            fs::write(path, json.as_bytes()).await?;
+                            wallpaper.reviewed_at = Some(Utc::now());
+                            library.save_wallpaper(&wallpaper)?;
                         }
                     };
                     drop(prompt_lock);
                 };
-                Ok(())
-
+                anyhow::Ok(())
             }
-        }() => {}
+        } => {}
     };
     shutdown_rx.recv().await;
 
     let history = history.lock().await;
-    save_history(&wallpapers_dir, &*history)?;
+    library.save_history(&output.name, &history)?;
     Ok(())
 }
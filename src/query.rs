@@ -0,0 +1,364 @@
+use std::str::FromStr;
+
+use rusqlite::types::Value;
+
+use crate::rating::{Category, Rating};
+
+// How far a ratio can be off and still match, enough for 3440x1440 to count as 21:9
+pub const RATIO_TOLERANCE: f64 = 0.1;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Comparison {
+    Equal(f64),
+    Greater(f64),
+    GreaterOrEqual(f64),
+    Less(f64),
+    LessOrEqual(f64),
+    Between(f64, f64),
+}
+
+impl Comparison {
+    fn parse(input: &str, parse_value: fn(&str) -> Option<f64>) -> Option<Comparison> {
+        if let Some(value) = input.strip_prefix(">=") {
+            Some(Comparison::GreaterOrEqual(parse_value(value)?))
+        } else if let Some(value) = input.strip_prefix("<=") {
+            Some(Comparison::LessOrEqual(parse_value(value)?))
+        } else if let Some(value) = input.strip_prefix('>') {
+            Some(Comparison::Greater(parse_value(value)?))
+        } else if let Some(value) = input.strip_prefix('<') {
+            Some(Comparison::Less(parse_value(value)?))
+        } else if let Some((from, to)) = input.split_once("..") {
+            match (from, to) {
+                ("", "") => None,
+                ("", to) => Some(Comparison::LessOrEqual(parse_value(to)?)),
+                (from, "") => Some(Comparison::GreaterOrEqual(parse_value(from)?)),
+                (from, to) => Some(Comparison::Between(parse_value(from)?, parse_value(to)?)),
+            }
+        } else {
+            Some(Comparison::Equal(parse_value(
+                input.strip_prefix('=').unwrap_or(input),
+            )?))
+        }
+    }
+
+    fn sql(&self, expression: &str, tolerance: f64, params: &mut Vec<Value>) -> String {
+        let (operator, bounds) = match self {
+            Comparison::Equal(expected) => (
+                "BETWEEN ? AND ?",
+                vec![expected - tolerance, expected + tolerance],
+            ),
+            Comparison::Greater(bound) => ("> ?", vec![*bound]),
+            Comparison::GreaterOrEqual(bound) => (">= ?", vec![bound - tolerance]),
+            Comparison::Less(bound) => ("< ?", vec![*bound]),
+            Comparison::LessOrEqual(bound) => ("<= ?", vec![bound + tolerance]),
+            Comparison::Between(from, to) => {
+                ("BETWEEN ? AND ?", vec![from - tolerance, to + tolerance])
+            }
+        };
+        params.extend(bounds.into_iter().map(Value::Real));
+        format!("{expression} {operator}")
+    }
+}
+
+fn parse_number(input: &str) -> Option<f64> {
+    input.parse::<i64>().ok().map(|number| number as f64)
+}
+
+pub fn parse_ratio(input: &str) -> Option<f64> {
+    match input.split_once([':', 'x']) {
+        Some((width, height)) => {
+            let width: f64 = width.parse().ok()?;
+            let height: f64 = height.parse().ok()?;
+            (height > 0.0).then(|| width / height)
+        }
+        None => input.parse().ok(),
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Condition {
+    Category(Option<Category>),
+    Rating(Rating),
+    Source(String),
+    Cropped(bool),
+    Md5(String),
+    Tag(String),
+    Artist(String),
+    Uploader(String),
+    Score(Comparison),
+    Width(Comparison),
+    Height(Comparison),
+    Ratio(Comparison),
+}
+
+impl Condition {
+    // An expression over the wallpapers table, its values are appended to `params` in order
+    pub fn sql(&self, params: &mut Vec<Value>) -> String {
+        match self {
+            Condition::Category(None) => "category IS NULL".to_owned(),
+            Condition::Category(Some(category)) => {
+                params.push(category.to_string().into());
+                "category = ?".to_owned()
+            }
+            Condition::Rating(rating) => {
+                params.push(rating.to_string().into());
+                "rating = ?".to_owned()
+            }
+            Condition::Source(source) => {
+                params.push(source.clone().into());
+                "source = ?".to_owned()
+            }
+            Condition::Cropped(true) => "json_extract(data, '$.crop_data') IS NOT NULL".to_owned(),
+            Condition::Cropped(false) => "json_extract(data, '$.crop_data') IS NULL".to_owned(),
+            Condition::Md5(md5) => {
+                params.push((md5.len() as i64).into());
+                params.push(md5.clone().into());
+                "substr(md5, 1, ?) = ?".to_owned()
+            }
+            Condition::Tag(tag) => {
+                params.push(tag.clone().into());
+                "md5 IN (SELECT md5 FROM wallpaper_tags WHERE tag = ?)".to_owned()
+            }
+            // One of the space separated artist tags
+            Condition::Artist(artist) => {
+                params.push(artist.to_lowercase().into());
+                "instr(' ' || lower(json_extract(data, '$.artist')) || ' ', ' ' || ? || ' ') > 0"
+                    .to_owned()
+            }
+            Condition::Uploader(uploader) => {
+                params.push(uploader.clone().into());
+                "json_extract(data, '$.uploader') = ? COLLATE NOCASE".to_owned()
+            }
+            Condition::Score(comparison) => comparison.sql("score", 0.0, params),
+            Condition::Width(comparison) => comparison.sql("width", 0.0, params),
+            Condition::Height(comparison) => comparison.sql("height", 0.0, params),
+            Condition::Ratio(comparison) => format!(
+                "height > 0 AND {}",
+                comparison.sql("CAST(width AS REAL) / height", RATIO_TOLERANCE, params)
+            ),
+        }
+    }
+
+    fn parse(field: &str, value: &str) -> Result<Condition, QueryParseError> {
+        let invalid = || QueryParseError::InvalidValue(field.to_owned(), value.to_owned());
+
+        match field {
+            "category" => match value {
+                "none" | "uncategorized" => Ok(Condition::Category(None)),
+                value => match Category::from_str(value) {
+                    Ok(Category::Any) | Err(_) => Err(invalid()),
+                    Ok(category) => Ok(Condition::Category(Some(category))),
+                },
+            },
+            "rating" => match Rating::from_str(value) {
+                Ok(Rating::Any) | Err(_) => Err(invalid()),
+                Ok(rating) => Ok(Condition::Rating(rating)),
+            },
+            "source" => Ok(Condition::Source(value.to_owned())),
+            "cropped" => match value {
+                "yes" | "true" | "y" => Ok(Condition::Cropped(true)),
+                "no" | "false" | "n" => Ok(Condition::Cropped(false)),
+                _ => Err(invalid()),
+            },
+            "md5" => Ok(Condition::Md5(value.to_lowercase())),
+            "tag" => Ok(Condition::Tag(value.to_lowercase())),
+            "artist" => Ok(Condition::Artist(value.to_owned())),
+            "uploader" => Ok(Condition::Uploader(value.to_owned())),
+            "score" => Comparison::parse(value, parse_number)
+                .map(Condition::Score)
+                .ok_or_else(invalid),
+            "width" => Comparison::parse(value, parse_number)
+                .map(Condition::Width)
+                .ok_or_else(invalid),
+            "height" => Comparison::parse(value, parse_number)
+                .map(Condition::Height)
+                .ok_or_else(invalid),
+            "ratio" => Comparison::parse(value, parse_ratio)
+                .map(Condition::Ratio)
+                .ok_or_else(invalid),
+            field => Err(QueryParseError::UnknownField(field.to_owned())),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Term {
+    negated: bool,
+    alternatives: Vec<Condition>,
+}
+
+impl Term {
+    fn sql(&self, params: &mut Vec<Value>) -> String {
+        let alternatives = self
+            .alternatives
+            .iter()
+            .map(|condition| format!("({})", condition.sql(params)))
+            .collect::<Vec<_>>()
+            .join(" OR ");
+        match self.negated {
+            true => format!("NOT ({alternatives})"),
+            false => format!("({alternatives})"),
+        }
+    }
+}
+
+impl From<Condition> for Term {
+    fn from(condition: Condition) -> Self {
+        Term {
+            negated: false,
+            alternatives: vec![condition],
+        }
+    }
+}
+
+impl FromStr for Term {
+    type Err = QueryParseError;
+
+    fn from_str(input: &str) -> Result<Term, Self::Err> {
+        let (negated, term) = match input.strip_prefix('-') {
+            Some(term) => (true, term),
+            None => (false, input),
+        };
+        let (field, values) = term
+            .split_once(':')
+            .ok_or_else(|| QueryParseError::MissingValue(input.to_owned()))?;
+
+        Ok(Term {
+            negated,
+            alternatives: values
+                .split('|')
+                .map(|value| Condition::parse(&field.to_lowercase(), value))
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum QueryParseError {
+    #[error("Expected field:value, got {0}")]
+    MissingValue(String),
+    #[error("Unknown field {0}, must be one of: category, rating, source, cropped, md5, tag, artist, uploader, score, width, height, ratio")]
+    UnknownField(String),
+    #[error("Invalid value {1} for field {0}")]
+    InvalidValue(String, String),
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Query {
+    terms: Vec<Term>,
+}
+
+impl Query {
+    // A WHERE clause for the wallpapers table, the empty query matches everything
+    pub fn sql(&self, params: &mut Vec<Value>) -> String {
+        match self.terms.is_empty() {
+            true => "1".to_owned(),
+            false => self
+                .terms
+                .iter()
+                .map(|term| term.sql(params))
+                .collect::<Vec<_>>()
+                .join(" AND "),
+        }
+    }
+
+    pub fn and(mut self, condition: Condition) -> Self {
+        self.terms.push(Term::from(condition));
+        self
+    }
+}
+
+impl From<Condition> for Query {
+    fn from(condition: Condition) -> Self {
+        Query::default().and(condition)
+    }
+}
+
+impl FromStr for Query {
+    type Err = QueryParseError;
+
+    fn from_str(input: &str) -> Result<Query, Self::Err> {
+        Ok(Query {
+            terms: input
+                .split_whitespace()
+                .map(Term::from_str)
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_terms() {
+        let query = Query::from_str("category:liked -tag:cat rating:safe|q").unwrap();
+        assert_eq!(
+            query.terms,
+            vec![
+                Term::from(Condition::Category(Some(Category::Liked))),
+                Term {
+                    negated: true,
+                    alternatives: vec![Condition::Tag("cat".to_owned())],
+                },
+                Term {
+                    negated: false,
+                    alternatives: vec![
+                        Condition::Rating(Rating::Safe),
+                        Condition::Rating(Rating::Questionable),
+                    ],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_comparisons() {
+        let query = Query::from_str("score:>=10 width:1920.. height:..1080 ratio:16:9").unwrap();
+        assert_eq!(
+            query,
+            Query::from(Condition::Score(Comparison::GreaterOrEqual(10.0)))
+                .and(Condition::Width(Comparison::GreaterOrEqual(1920.0)))
+                .and(Condition::Height(Comparison::LessOrEqual(1080.0)))
+                .and(Condition::Ratio(Comparison::Equal(16.0 / 9.0)))
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_terms() {
+        assert!(matches!(
+            Query::from_str("cat"),
+            Err(QueryParseError::MissingValue(_))
+        ));
+        assert!(matches!(
+            Query::from_str("author:someone"),
+            Err(QueryParseError::UnknownField(_))
+        ));
+        assert!(matches!(
+            Query::from_str("score:high"),
+            Err(QueryParseError::InvalidValue(..))
+        ));
+        assert!(matches!(
+            Query::from_str("rating:any"),
+            Err(QueryParseError::InvalidValue(..))
+        ));
+    }
+
+    #[test]
+    fn builds_sql() {
+        let mut params = vec![];
+        let sql = Query::from_str("-category:none|liked source:local")
+            .unwrap()
+            .sql(&mut params);
+        assert_eq!(
+            sql,
+            "NOT ((category IS NULL) OR (category = ?)) AND ((source = ?))"
+        );
+        assert_eq!(
+            params,
+            vec![Value::from("Liked".to_owned()), "local".to_owned().into()]
+        );
+        assert_eq!(Query::default().sql(&mut vec![]), "1");
+    }
+}
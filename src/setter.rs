@@ -0,0 +1,430 @@
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    process::{Child, Command, ExitStatus, Stdio},
+    sync::{LazyLock, Mutex},
+    thread,
+    time::{Duration, Instant},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{config::runtime_dir, output::DEFAULT_OUTPUT};
+
+// How long a setter that keeps running (like swaybg) gets to fail before it counts as working.
+// There is no signal for when it has drawn, so this is a guess, a slower start still flashes black
+// when the old setter is stopped
+const STARTUP_GRACE: Duration = Duration::from_millis(500);
+
+// Setters started by this process, so a long running one like a review session or the daemon can
+// reap them instead of leaving zombies
+static STARTED: LazyLock<Mutex<HashMap<u32, Child>>> = LazyLock::new(Default::default);
+
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Hash, Clone, Ord, PartialOrd)]
+#[serde(rename_all = "lowercase")]
+pub enum Setter {
+    Swaybg,
+    Swww,
+    Hyprpaper,
+    Feh,
+    Xwallpaper,
+    Gnome,
+    Kde,
+    // set_wallpaper_command, `{}` is the image and `{output}` the output name
+    Command,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum SetWallpaperError {
+    #[error("Invalid set wallpaper command, check your config")]
+    InvalidCommand(#[from] shell_words::ParseError),
+    #[error("Set wallpaper command is empty, check your config")]
+    EmptyCommand,
+    #[error("Failed to start {0}: {1}")]
+    NotStarted(String, std::io::Error),
+    #[error("{0} failed with {1}")]
+    Failed(String, ExitStatus),
+    #[error("The {0} setter sets every output at once, it can't set {1} alone")]
+    AllOutputsOnly(&'static str, String),
+    #[error("The {0} setter doesn't take setter_output_args, check your config")]
+    OutputArgsUnsupported(&'static str),
+}
+
+struct Invocation {
+    args: Vec<String>,
+    // Keeps running to show the wallpaper instead of exiting once it is set
+    persistent: bool,
+}
+
+impl Invocation {
+    fn once(args: Vec<String>) -> Self {
+        Invocation {
+            args,
+            persistent: false,
+        }
+    }
+
+    fn persistent(args: Vec<String>) -> Self {
+        Invocation {
+            args,
+            persistent: true,
+        }
+    }
+
+    // The setter if it is still running to show the wallpaper
+    fn run(&self) -> Result<Option<Child>, SetWallpaperError> {
+        let (program, args) = self
+            .args
+            .split_first()
+            .ok_or(SetWallpaperError::EmptyCommand)?;
+        let mut child = Command::new(program)
+            .args(args)
+            .stdin(Stdio::null())
+            .spawn()
+            .map_err(|err| SetWallpaperError::NotStarted(program.to_owned(), err))?;
+        let failed = |status: ExitStatus| SetWallpaperError::Failed(program.to_owned(), status);
+
+        if !self.persistent {
+            let status = child
+                .wait()
+                .map_err(|err| SetWallpaperError::NotStarted(program.to_owned(), err))?;
+            return match status.success() {
+                true => Ok(None),
+                false => Err(failed(status)),
+            };
+        }
+
+        let started = Instant::now();
+        while started.elapsed() < STARTUP_GRACE {
+            match child.try_wait() {
+                Ok(Some(status)) if !status.success() => return Err(failed(status)),
+                Ok(Some(_)) => return Ok(None),
+                Ok(None) => thread::sleep(Duration::from_millis(20)),
+                Err(err) => return Err(SetWallpaperError::NotStarted(program.to_owned(), err)),
+            }
+        }
+        Ok(Some(child))
+    }
+}
+
+// Records the setter now showing the output and stops the ones it covers. The old setter keeps
+// showing its wallpaper until the new one is up, so there is no black flash in between
+fn replace(output: &str, started: Option<Child>) {
+    let previous = take_pid_files(output);
+    if let Some(child) = started {
+        if let Err(err) = write_pid_file(output, child.id()) {
+            eprintln!("Could not record the setter pid, it won't be replaced: {err}");
+        }
+        STARTED.lock().unwrap().insert(child.id(), child);
+    }
+    for (pid, started_at) in previous {
+        stop(pid, started_at);
+    }
+}
+
+fn pid_file(runtime_dir: &Path, output: &str) -> PathBuf {
+    runtime_dir.join(format!("setter-{output}.pid"))
+}
+
+// When the process started, in clock ticks since boot. Together with the pid it tells a setter
+// apart from an unrelated process that got its pid later
+fn start_time(pid: u32) -> Option<u64> {
+    let stat = fs::read_to_string(format!("/proc/{pid}/stat")).ok()?;
+    // The name in parentheses may contain spaces, the start time is the 20th field after it
+    let (_, fields) = stat.rsplit_once(')')?;
+    fields.split_whitespace().nth(19)?.parse().ok()
+}
+
+// The pid and start time of a setter last started for an output
+fn read_pid_file(path: &Path) -> Option<(u32, u64)> {
+    let content = fs::read_to_string(path).ok()?;
+    let (pid, start_time) = content.trim().split_once(' ')?;
+    Some((pid.parse().ok()?, start_time.parse().ok()?))
+}
+
+// Removes the pid files of the setters showing the output, a setter for every output covers each
+// named one and the other way round
+fn take_pid_files(output: &str) -> Vec<(u32, u64)> {
+    let Ok(runtime_dir) = runtime_dir() else {
+        return vec![];
+    };
+    let paths: Vec<PathBuf> = if output == DEFAULT_OUTPUT {
+        fs::read_dir(&runtime_dir)
+            .into_iter()
+            .flatten()
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| {
+                path.file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| name.starts_with("setter-") && name.ends_with(".pid"))
+            })
+            .collect()
+    } else {
+        vec![
+            pid_file(&runtime_dir, output),
+            pid_file(&runtime_dir, DEFAULT_OUTPUT),
+        ]
+    };
+    paths
+        .iter()
+        .filter_map(|path| {
+            let previous = read_pid_file(path);
+            let _ = fs::remove_file(path);
+            previous
+        })
+        .collect()
+}
+
+fn write_pid_file(output: &str, pid: u32) -> std::io::Result<()> {
+    let start_time = start_time(pid).ok_or(std::io::ErrorKind::NotFound)?;
+    fs::write(
+        pid_file(&runtime_dir()?, output),
+        format!("{pid} {start_time}"),
+    )
+}
+
+fn stop(pid: u32, started_at: u64) {
+    if let Some(mut child) = STARTED.lock().unwrap().remove(&pid) {
+        let _ = child.kill();
+        let _ = child.wait();
+        return;
+    }
+
+    if start_time(pid) != Some(started_at) {
+        return;
+    }
+    let _ = Command::new("kill")
+        .arg(pid.to_string())
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .status();
+}
+
+fn args(words: &[&str]) -> Vec<String> {
+    words.iter().map(|word| word.to_string()).collect()
+}
+
+// A file:// URI for gsettings, everything but unreserved characters and slashes is percent-encoded
+fn file_uri(path: &str) -> String {
+    let mut uri = String::from("file://");
+    for byte in path.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' => {
+                uri.push(byte as char)
+            }
+            _ => uri.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    uri
+}
+
+// Replaces the placeholders word by word, so paths with spaces stay one argument
+fn template(command: &str, path: &str, output: &str) -> Result<Vec<String>, SetWallpaperError> {
+    Ok(shell_words::split(command)?
+        .into_iter()
+        .map(|word| word.replace("{output}", output).replace("{}", path))
+        .collect())
+}
+
+impl Setter {
+    fn name(&self) -> &'static str {
+        match self {
+            Setter::Swaybg => "swaybg",
+            Setter::Swww => "swww",
+            Setter::Hyprpaper => "hyprpaper",
+            Setter::Feh => "feh",
+            Setter::Xwallpaper => "xwallpaper",
+            Setter::Gnome => "gnome",
+            Setter::Kde => "kde",
+            Setter::Command => "command",
+        }
+    }
+
+    // These put the image on the whole desktop, setting outputs one by one would leave only the
+    // last image
+    fn sets_all_outputs(&self) -> bool {
+        matches!(self, Setter::Feh | Setter::Gnome | Setter::Kde)
+    }
+
+    // Only these end with the command for the output, where extra arguments can go
+    fn takes_output_args(&self) -> bool {
+        matches!(
+            self,
+            Setter::Swaybg | Setter::Swww | Setter::Xwallpaper | Setter::Command
+        )
+    }
+
+    fn invocations(
+        &self,
+        command: &str,
+        path: &str,
+        output: &str,
+    ) -> Result<Vec<Invocation>, SetWallpaperError> {
+        let all_outputs = output == DEFAULT_OUTPUT;
+        Ok(match self {
+            Setter::Swaybg => vec![Invocation::persistent(args(&[
+                "swaybg", "--output", output, "--mode", "fill", "--image", path,
+            ]))],
+            Setter::Swww => {
+                let mut swww = args(&["swww", "img", path]);
+                if !all_outputs {
+                    swww.extend(args(&["--outputs", output]));
+                }
+                vec![Invocation::once(swww)]
+            }
+            Setter::Hyprpaper => {
+                let monitor = if all_outputs { "" } else { output };
+                vec![
+                    Invocation::once(args(&["hyprctl", "hyprpaper", "preload", path])),
+                    Invocation::once(args(&[
+                        "hyprctl",
+                        "hyprpaper",
+                        "wallpaper",
+                        &format!("{monitor},{path}"),
+                    ])),
+                    // Otherwise every wallpaper ever set stays preloaded
+                    Invocation::once(args(&["hyprctl", "hyprpaper", "unload", "unused"])),
+                ]
+            }
+            Setter::Feh => vec![Invocation::once(args(&[
+                "feh",
+                "--no-fehbg",
+                "--bg-fill",
+                path,
+            ]))],
+            Setter::Xwallpaper => {
+                let mut xwallpaper = args(&["xwallpaper"]);
+                if !all_outputs {
+                    xwallpaper.extend(args(&["--output", output]));
+                }
+                xwallpaper.extend(args(&["--zoom", path]));
+                vec![Invocation::once(xwallpaper)]
+            }
+            Setter::Gnome => {
+                let uri = file_uri(path);
+                ["picture-uri", "picture-uri-dark"]
+                    .into_iter()
+                    .map(|key| {
+                        Invocation::once(args(&[
+                            "gsettings",
+                            "set",
+                            "org.gnome.desktop.background",
+                            key,
+                            &uri,
+                        ]))
+                    })
+                    .collect()
+            }
+            Setter::Kde => vec![Invocation::once(args(&[
+                "plasma-apply-wallpaperimage",
+                path,
+            ]))],
+            Setter::Command => vec![Invocation::persistent(template(command, path, output)?)],
+        })
+    }
+
+    // `output_args` are extra arguments for this output, added to the last command
+    pub fn set(
+        &self,
+        command: &str,
+        path: &Path,
+        output: &str,
+        targeted: bool,
+        output_args: Option<&String>,
+    ) -> Result<(), SetWallpaperError> {
+        if self.sets_all_outputs() && targeted {
+            return Err(SetWallpaperError::AllOutputsOnly(
+                self.name(),
+                output.to_owned(),
+            ));
+        }
+        if output_args.is_some() && !self.takes_output_args() {
+            return Err(SetWallpaperError::OutputArgsUnsupported(self.name()));
+        }
+        // Whatever was running for single outputs is covered now
+        let output = match self.sets_all_outputs() {
+            true => DEFAULT_OUTPUT,
+            false => output,
+        };
+
+        let path = path.to_string_lossy();
+        let mut invocations = self.invocations(command, &path, output)?;
+        if let (Some(output_args), Some(last)) = (output_args, invocations.last_mut()) {
+            last.args.extend(shell_words::split(output_args)?);
+        }
+        // A setter that exits leaves nothing to track, the old one for the output is stopped all
+        // the same, for example after switching from swaybg to swww
+        let mut started = None;
+        for invocation in invocations {
+            started = invocation.run()?;
+        }
+        replace(output, started);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fills_placeholders_per_word() {
+        assert_eq!(
+            template(
+                "swaybg -o {output} -i {} -m fill",
+                "/my walls/a.png",
+                "DP-1"
+            )
+            .unwrap(),
+            args(&[
+                "swaybg",
+                "-o",
+                "DP-1",
+                "-i",
+                "/my walls/a.png",
+                "-m",
+                "fill"
+            ])
+        );
+        assert_eq!(
+            template("sh -c 'echo {output}: {}'", "a.png", "*").unwrap(),
+            args(&["sh", "-c", "echo *: a.png"])
+        );
+        assert!(matches!(
+            template("feh '{}", "a.png", "*"),
+            Err(SetWallpaperError::InvalidCommand(_))
+        ));
+    }
+
+    #[test]
+    fn encodes_file_uris() {
+        assert_eq!(
+            file_uri("/home/me/my walls/#1 100%.png"),
+            "file:///home/me/my%20walls/%231%20100%25.png"
+        );
+        assert_eq!(
+            file_uri("/wallpapers/ü.jpg"),
+            "file:///wallpapers/%C3%BC.jpg"
+        );
+    }
+
+    #[test]
+    fn whole_desktop_setters_reject_targeted_outputs() {
+        assert!(matches!(
+            Setter::Feh.set("", Path::new("a.png"), "DP-1", true, None),
+            Err(SetWallpaperError::AllOutputsOnly("feh", _))
+        ));
+        assert!(matches!(
+            Setter::Gnome.set(
+                "",
+                Path::new("a.png"),
+                "DP-1",
+                false,
+                Some(&"--x".to_owned())
+            ),
+            Err(SetWallpaperError::OutputArgsUnsupported("gnome"))
+        ));
+    }
+}
@@ -0,0 +1,139 @@
+use std::{fmt, str::FromStr};
+
+use chrono::{DateTime, Utc};
+use futures::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+
+use crate::{config::Config, rating::Rating, wallpaper_list::Filters};
+
+pub mod danbooru;
+pub mod gelbooru;
+pub mod moebooru;
+pub mod wallhaven;
+
+use danbooru::Danbooru;
+use gelbooru::Gelbooru;
+use moebooru::Moebooru;
+use wallhaven::Wallhaven;
+
+// Posts fetched for one search at most, a broad query would otherwise walk the whole site
+pub const MAX_OFFSET: usize = 20000;
+
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Hash, Clone, Ord, PartialOrd)]
+pub struct Post {
+    pub source: String,
+    pub id: String,
+    pub md5: Option<String>,
+    pub file_url: String,
+    pub width: i32,
+    pub height: i32,
+    pub score: i32,
+    pub rating: Rating,
+    pub tags: Vec<String>,
+    // Artist tags where the site types its tags, the account that posted it otherwise
+    pub artist: Option<String>,
+    pub uploader: Option<String>,
+    pub source_url: Option<String>,
+    pub post_url: Option<String>,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+pub fn split_tags(tags: &str) -> Vec<String> {
+    tags.split_whitespace().map(str::to_owned).collect()
+}
+
+pub fn non_empty(value: String) -> Option<String> {
+    (!value.is_empty()).then_some(value)
+}
+
+pub trait WallpaperSource: Send + Sync {
+    fn name(&self) -> &str;
+
+    // Everything besides the filters that changes the posts a search returns, cached lists are
+    // kept per key
+    fn cache_key(&self) -> String {
+        self.name().to_owned()
+    }
+
+    fn fetch_wallpaper_list<'a>(
+        &'a self,
+        filters: &'a Filters,
+    ) -> BoxFuture<'a, Result<Vec<Post>, reqwest::Error>>;
+
+    fn download_url(&self, post: &Post) -> String {
+        post.file_url.clone()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, PartialOrd, Eq, Ord, Hash, Serialize)]
+pub enum SourceKind {
+    Danbooru,
+    Gelbooru,
+    Wallhaven,
+    Moebooru(String),
+}
+
+impl SourceKind {
+    pub fn source(&self, config: &Config) -> Result<Box<dyn WallpaperSource>, SourceError> {
+        match self {
+            SourceKind::Danbooru => Ok(Box::new(Danbooru::new(
+                config.danbooru_login.clone(),
+                config.danbooru_api_key.clone(),
+            ))),
+            SourceKind::Gelbooru => Ok(Box::new(Gelbooru::new(
+                config.gelbooru_user_id.clone(),
+                config.gelbooru_api_key.clone(),
+            ))),
+            SourceKind::Wallhaven => Ok(Box::new(Wallhaven::new(config.wallhaven.clone()))),
+            SourceKind::Moebooru(name) => match config.moebooru_sources.get(name) {
+                Some(base_url) => Ok(Box::new(Moebooru::new(name, base_url))),
+                None => Err(SourceError::NotConfigured(
+                    name.to_owned(),
+                    config
+                        .moebooru_sources
+                        .keys()
+                        .cloned()
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                )),
+            },
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum SourceError {
+    #[error("Source {0} is not configured, must be one of: {1}")]
+    NotConfigured(String, String),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum SourceKindParseError {
+    #[error("Source name can not be empty")]
+    Empty,
+}
+
+impl FromStr for SourceKind {
+    type Err = SourceKindParseError;
+
+    fn from_str(input: &str) -> Result<SourceKind, Self::Err> {
+        match input {
+            "" => Err(SourceKindParseError::Empty),
+            "Danbooru" | "danbooru" => Ok(SourceKind::Danbooru),
+            "Gelbooru" | "gelbooru" => Ok(SourceKind::Gelbooru),
+            "Wallhaven" | "wallhaven" => Ok(SourceKind::Wallhaven),
+            name => Ok(SourceKind::Moebooru(name.to_owned())),
+        }
+    }
+}
+
+impl fmt::Display for SourceKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SourceKind::Danbooru => write!(f, "danbooru"),
+            SourceKind::Gelbooru => write!(f, "gelbooru"),
+            SourceKind::Wallhaven => write!(f, "wallhaven"),
+            SourceKind::Moebooru(name) => write!(f, "{name}"),
+        }
+    }
+}
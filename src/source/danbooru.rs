@@ -0,0 +1,147 @@
+use chrono::{DateTime, Utc};
+use futures::{future::BoxFuture, FutureExt};
+use serde::{Deserialize, Serialize};
+
+use super::{non_empty, split_tags, Post, WallpaperSource, MAX_OFFSET};
+use crate::{
+    rating::{KonachanRatingFilter, Rating},
+    wallpaper_list::Filters,
+};
+
+const BASE_URL: &str = "https://danbooru.donmai.us";
+
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Hash, Clone, Ord, PartialOrd)]
+pub enum DanbooruRating {
+    #[serde(rename = "g")]
+    General,
+    #[serde(rename = "s")]
+    Sensitive,
+    #[serde(rename = "q")]
+    Questionable,
+    #[serde(rename = "e")]
+    Explicit,
+}
+
+impl From<DanbooruRating> for Rating {
+    fn from(rating: DanbooruRating) -> Self {
+        match rating {
+            DanbooruRating::General | DanbooruRating::Sensitive => Rating::Safe,
+            DanbooruRating::Questionable => Rating::Questionable,
+            DanbooruRating::Explicit => Rating::Explicit,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Hash, Clone, Ord, PartialOrd)]
+pub struct DanbooruPost {
+    pub id: u64,
+    pub md5: Option<String>,
+    pub file_url: Option<String>,
+    pub image_width: i32,
+    pub image_height: i32,
+    pub score: i32,
+    pub rating: DanbooruRating,
+    pub tag_string: String,
+    pub tag_string_artist: String,
+    pub source: String,
+    pub created_at: DateTime<Utc>,
+}
+
+pub struct Danbooru {
+    login: Option<String>,
+    api_key: Option<String>,
+}
+
+fn rating_query(rating: &KonachanRatingFilter) -> &'static str {
+    match rating {
+        KonachanRatingFilter::Safe => "rating:g,s",
+        KonachanRatingFilter::Questionable => "rating:q",
+        KonachanRatingFilter::Explicit => "rating:e",
+        KonachanRatingFilter::QuestionableAndExplicit => "rating:q,e",
+        KonachanRatingFilter::QuestionableAndSafe => "rating:g,s,q",
+    }
+}
+
+impl Danbooru {
+    pub fn new(login: Option<String>, api_key: Option<String>) -> Self {
+        Danbooru { login, api_key }
+    }
+
+    async fn fetch(&self, filters: &Filters) -> Result<Vec<Post>, reqwest::Error> {
+        let limit = 200;
+        let mut page = None;
+        let mut wallpapers: Vec<DanbooruPost> = vec![];
+        let client = reqwest::Client::new();
+        // Anonymous searches take 2 tags, and the dimensions count as tags
+        let logged_in = self.login.is_some() && self.api_key.is_some();
+        let tags = filters.booru_tag_query(rating_query(&filters.rating), logged_in);
+
+        loop {
+            let mut request = client
+                .get(format!("{BASE_URL}/posts.json"))
+                .query(&[("limit", limit.to_string()), ("tags", tags.clone())]);
+            if let Some(before_id) = page {
+                request = request.query(&[("page", format!("b{before_id}"))]);
+            }
+            if let (Some(login), Some(api_key)) = (&self.login, &self.api_key) {
+                request = request.query(&[("login", login), ("api_key", api_key)]);
+            }
+
+            let mut response = request
+                .send()
+                .await?
+                .error_for_status()?
+                .json::<Vec<DanbooruPost>>()
+                .await?;
+
+            let fetched = response.len();
+            page = response.iter().map(|post| post.id).min();
+            wallpapers.append(&mut response);
+
+            if fetched < limit || wallpapers.len() >= MAX_OFFSET {
+                break;
+            }
+        }
+
+        Ok(wallpapers
+            .into_iter()
+            .filter_map(|wallpaper| {
+                Some(Post {
+                    source: self.name().to_owned(),
+                    id: wallpaper.id.to_string(),
+                    md5: Some(wallpaper.md5?),
+                    file_url: wallpaper.file_url?,
+                    width: wallpaper.image_width,
+                    height: wallpaper.image_height,
+                    score: wallpaper.score,
+                    rating: wallpaper.rating.into(),
+                    tags: split_tags(&wallpaper.tag_string),
+                    artist: non_empty(wallpaper.tag_string_artist),
+                    uploader: None,
+                    source_url: non_empty(wallpaper.source),
+                    post_url: Some(format!("{BASE_URL}/posts/{}", wallpaper.id)),
+                    created_at: Some(wallpaper.created_at),
+                })
+            })
+            .collect())
+    }
+}
+
+impl WallpaperSource for Danbooru {
+    fn name(&self) -> &str {
+        "danbooru"
+    }
+
+    // Accounts search differently and may see posts anonymous users don't
+    fn cache_key(&self) -> String {
+        let login = self.login.as_deref().unwrap_or_default();
+        format!("{} {login}", self.name())
+    }
+
+    fn fetch_wallpaper_list<'a>(
+        &'a self,
+        filters: &'a Filters,
+    ) -> BoxFuture<'a, Result<Vec<Post>, reqwest::Error>> {
+        self.fetch(filters).boxed()
+    }
+}
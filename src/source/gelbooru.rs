@@ -0,0 +1,159 @@
+use chrono::{DateTime, Utc};
+use futures::{future::BoxFuture, FutureExt};
+use serde::{Deserialize, Serialize};
+
+use super::{non_empty, split_tags, Post, WallpaperSource, MAX_OFFSET};
+use crate::{
+    rating::{KonachanRatingFilter, Rating},
+    wallpaper_list::Filters,
+};
+
+const BASE_URL: &str = "https://gelbooru.com";
+
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Hash, Clone, Ord, PartialOrd)]
+#[serde(rename_all = "lowercase")]
+pub enum GelbooruRating {
+    #[serde(alias = "safe")]
+    General,
+    Sensitive,
+    Questionable,
+    Explicit,
+}
+
+impl From<GelbooruRating> for Rating {
+    fn from(rating: GelbooruRating) -> Self {
+        match rating {
+            GelbooruRating::General | GelbooruRating::Sensitive => Rating::Safe,
+            GelbooruRating::Questionable => Rating::Questionable,
+            GelbooruRating::Explicit => Rating::Explicit,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Hash, Clone, Ord, PartialOrd)]
+pub struct GelbooruPost {
+    pub id: u64,
+    pub md5: String,
+    pub file_url: String,
+    pub width: i32,
+    pub height: i32,
+    pub score: i32,
+    pub rating: GelbooruRating,
+    pub tags: String,
+    pub owner: String,
+    pub source: String,
+    pub created_at: String,
+}
+
+#[derive(Deserialize, Debug)]
+struct GelbooruResponse {
+    #[serde(default)]
+    post: Vec<GelbooruPost>,
+}
+
+pub struct Gelbooru {
+    user_id: Option<String>,
+    api_key: Option<String>,
+}
+
+fn rating_query(rating: &KonachanRatingFilter) -> &'static str {
+    match rating {
+        KonachanRatingFilter::Safe => "-rating:questionable -rating:explicit",
+        KonachanRatingFilter::Questionable => "rating:questionable",
+        KonachanRatingFilter::Explicit => "rating:explicit",
+        KonachanRatingFilter::QuestionableAndExplicit => "-rating:general -rating:sensitive",
+        KonachanRatingFilter::QuestionableAndSafe => "-rating:explicit",
+    }
+}
+
+impl Gelbooru {
+    pub fn new(user_id: Option<String>, api_key: Option<String>) -> Self {
+        Gelbooru { user_id, api_key }
+    }
+
+    async fn fetch(&self, filters: &Filters) -> Result<Vec<Post>, reqwest::Error> {
+        let limit = 100;
+        let mut pid = 0;
+        let mut wallpapers: Vec<GelbooruPost> = vec![];
+        let client = reqwest::Client::new();
+        let tags = filters.booru_tag_query(rating_query(&filters.rating), true);
+
+        loop {
+            let mut request = client.get(format!("{BASE_URL}/index.php")).query(&[
+                ("page", "dapi".to_string()),
+                ("s", "post".to_string()),
+                ("q", "index".to_string()),
+                ("json", "1".to_string()),
+                ("limit", limit.to_string()),
+                ("pid", pid.to_string()),
+                ("tags", tags.clone()),
+            ]);
+            if let (Some(user_id), Some(api_key)) = (&self.user_id, &self.api_key) {
+                request = request.query(&[("user_id", user_id), ("api_key", api_key)]);
+            }
+
+            let mut response = request
+                .send()
+                .await?
+                .error_for_status()?
+                .json::<GelbooruResponse>()
+                .await?
+                .post;
+
+            let fetched = response.len();
+            wallpapers.append(&mut response);
+
+            pid += 1;
+            if fetched < limit || pid * limit >= MAX_OFFSET {
+                break;
+            }
+        }
+
+        Ok(wallpapers
+            .into_iter()
+            .map(|wallpaper| Post {
+                source: self.name().to_owned(),
+                id: wallpaper.id.to_string(),
+                md5: Some(wallpaper.md5),
+                file_url: wallpaper.file_url,
+                width: wallpaper.width,
+                height: wallpaper.height,
+                score: wallpaper.score,
+                rating: wallpaper.rating.into(),
+                tags: split_tags(&wallpaper.tags),
+                artist: None,
+                uploader: non_empty(wallpaper.owner),
+                source_url: non_empty(wallpaper.source),
+                post_url: Some(format!(
+                    "{BASE_URL}/index.php?page=post&s=view&id={}",
+                    wallpaper.id
+                )),
+                // Gelbooru uses the ctime format, for example "Sat Apr 08 13:25:38 -0500 2023"
+                created_at: DateTime::parse_from_str(
+                    &wallpaper.created_at,
+                    "%a %b %d %H:%M:%S %z %Y",
+                )
+                .ok()
+                .map(|created_at| created_at.with_timezone(&Utc)),
+            })
+            .collect())
+    }
+}
+
+impl WallpaperSource for Gelbooru {
+    fn name(&self) -> &str {
+        "gelbooru"
+    }
+
+    fn cache_key(&self) -> String {
+        let user_id = self.user_id.as_deref().unwrap_or_default();
+        format!("{} {user_id}", self.name())
+    }
+
+    fn fetch_wallpaper_list<'a>(
+        &'a self,
+        filters: &'a Filters,
+    ) -> BoxFuture<'a, Result<Vec<Post>, reqwest::Error>> {
+        self.fetch(filters).boxed()
+    }
+}
@@ -0,0 +1,102 @@
+use chrono::{TimeZone, Utc};
+use futures::{future::BoxFuture, FutureExt};
+use serde::{Deserialize, Serialize};
+
+use super::{non_empty, split_tags, Post, WallpaperSource, MAX_OFFSET};
+use crate::{rating::Rating, wallpaper_list::Filters};
+
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Hash, Clone, Ord, PartialOrd)]
+pub struct KonachanWallpaper {
+    pub id: u64,
+    pub md5: String,
+    pub file_url: String,
+    pub width: i32,
+    pub height: i32,
+    pub score: i32,
+    pub rating: Rating,
+    pub tags: String,
+    pub author: String,
+    pub source: String,
+    pub created_at: i64,
+}
+
+pub struct Moebooru {
+    name: String,
+    base_url: String,
+}
+
+impl Moebooru {
+    pub fn new(name: &str, base_url: &str) -> Self {
+        Moebooru {
+            name: name.to_owned(),
+            base_url: base_url.trim_end_matches('/').to_owned(),
+        }
+    }
+
+    async fn fetch(&self, filters: &Filters) -> Result<Vec<Post>, reqwest::Error> {
+        let mut page = 0;
+        let limit = 1000;
+        let mut wallpapers: Vec<KonachanWallpaper> = vec![];
+        let client = reqwest::Client::new();
+        let tags = filters.tag_query();
+
+        loop {
+            page += 1;
+            let mut response = client
+                .get(format!("{}/post.json", self.base_url))
+                .query(&[
+                    ("limit", limit.to_string()),
+                    ("tags", tags.clone()),
+                    ("page", page.to_string()),
+                ])
+                .send()
+                .await?
+                .json::<Vec<KonachanWallpaper>>()
+                .await?;
+
+            let fetched = response.len();
+            wallpapers.append(&mut response);
+
+            if fetched < limit || page * limit >= MAX_OFFSET {
+                break;
+            }
+        }
+
+        Ok(wallpapers
+            .into_iter()
+            .map(|wallpaper| Post {
+                source: self.name.clone(),
+                id: wallpaper.id.to_string(),
+                md5: Some(wallpaper.md5),
+                file_url: wallpaper.file_url,
+                width: wallpaper.width,
+                height: wallpaper.height,
+                score: wallpaper.score,
+                rating: wallpaper.rating,
+                tags: split_tags(&wallpaper.tags),
+                artist: None,
+                uploader: non_empty(wallpaper.author),
+                source_url: non_empty(wallpaper.source),
+                post_url: Some(format!("{}/post/show/{}", self.base_url, wallpaper.id)),
+                created_at: Utc.timestamp_opt(wallpaper.created_at, 0).single(),
+            })
+            .collect())
+    }
+}
+
+impl WallpaperSource for Moebooru {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn cache_key(&self) -> String {
+        format!("{} {}", self.name, self.base_url)
+    }
+
+    fn fetch_wallpaper_list<'a>(
+        &'a self,
+        filters: &'a Filters,
+    ) -> BoxFuture<'a, Result<Vec<Post>, reqwest::Error>> {
+        self.fetch(filters).boxed()
+    }
+}
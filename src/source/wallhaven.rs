@@ -0,0 +1,179 @@
+use chrono::{NaiveDateTime, TimeZone, Utc};
+use futures::{future::BoxFuture, FutureExt};
+use serde::{Deserialize, Serialize};
+
+use super::{non_empty, Post, WallpaperSource};
+use crate::{
+    cli::Range,
+    config::WallhavenConfig,
+    rating::{KonachanRatingFilter, Rating},
+    wallpaper_list::Filters,
+};
+
+const BASE_URL: &str = "https://wallhaven.cc/api/v1";
+
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Hash, Clone, Ord, PartialOrd)]
+#[serde(rename_all = "lowercase")]
+pub enum WallhavenPurity {
+    Sfw,
+    Sketchy,
+    Nsfw,
+}
+
+impl From<WallhavenPurity> for Rating {
+    fn from(purity: WallhavenPurity) -> Self {
+        match purity {
+            WallhavenPurity::Sfw => Rating::Safe,
+            WallhavenPurity::Sketchy => Rating::Questionable,
+            WallhavenPurity::Nsfw => Rating::Explicit,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Hash, Clone, Ord, PartialOrd)]
+pub struct WallhavenWallpaper {
+    pub id: String,
+    pub path: String,
+    pub dimension_x: i32,
+    pub dimension_y: i32,
+    pub favorites: i32,
+    pub purity: WallhavenPurity,
+    pub url: String,
+    pub source: String,
+    pub created_at: String,
+}
+
+#[derive(Deserialize, Debug)]
+struct WallhavenMeta {
+    last_page: u32,
+    seed: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+struct WallhavenResponse {
+    data: Vec<WallhavenWallpaper>,
+    meta: WallhavenMeta,
+}
+
+pub struct Wallhaven {
+    config: WallhavenConfig,
+}
+
+fn purity(rating: &KonachanRatingFilter) -> &'static str {
+    match rating {
+        KonachanRatingFilter::Safe => "100",
+        KonachanRatingFilter::Questionable => "010",
+        KonachanRatingFilter::Explicit => "001",
+        KonachanRatingFilter::QuestionableAndExplicit => "011",
+        KonachanRatingFilter::QuestionableAndSafe => "110",
+    }
+}
+
+fn lower_bound(range: &Range) -> Option<u16> {
+    match range {
+        Range::LeftBounded(num) | Range::Exactly(num) | Range::Between(num, _) => Some(*num),
+        Range::RightBounded(_) => None,
+    }
+}
+
+impl Wallhaven {
+    pub fn new(config: WallhavenConfig) -> Self {
+        Wallhaven { config }
+    }
+
+    async fn fetch(&self, filters: &Filters) -> Result<Vec<Post>, reqwest::Error> {
+        let mut page = 0;
+        let mut seed = None;
+        let mut wallpapers: Vec<WallhavenWallpaper> = vec![];
+        let client = reqwest::Client::new();
+        let Filters {
+            width,
+            height,
+            tags,
+            rating,
+            ..
+        } = filters;
+
+        loop {
+            page += 1;
+            let mut request = client.get(format!("{BASE_URL}/search")).query(&[
+                ("q", tags.clone().unwrap_or("".to_string())),
+                ("categories", self.config.categories.clone()),
+                ("purity", purity(rating).to_string()),
+                ("sorting", self.config.sorting.clone()),
+                ("page", page.to_string()),
+            ]);
+            if let (Some(width), Some(height)) = (lower_bound(width), lower_bound(height)) {
+                request = request.query(&[("atleast", format!("{width}x{height}"))]);
+            }
+            if let Some(ratios) = &self.config.ratios {
+                request = request.query(&[("ratios", ratios)]);
+            }
+            if let Some(seed) = &seed {
+                request = request.query(&[("seed", seed)]);
+            }
+            if let Some(api_key) = &self.config.api_key {
+                request = request.header("X-API-Key", api_key);
+            }
+
+            let mut response = request
+                .send()
+                .await?
+                .error_for_status()?
+                .json::<WallhavenResponse>()
+                .await?;
+
+            wallpapers.append(&mut response.data);
+            seed = response.meta.seed;
+
+            if page >= response.meta.last_page || page >= self.config.pages {
+                break;
+            }
+        }
+
+        Ok(wallpapers
+            .into_iter()
+            .map(|wallpaper| Post {
+                source: self.name().to_owned(),
+                id: wallpaper.id,
+                md5: None,
+                file_url: wallpaper.path,
+                width: wallpaper.dimension_x,
+                height: wallpaper.dimension_y,
+                score: wallpaper.favorites,
+                rating: wallpaper.purity.into(),
+                // Search results do not include tags or the uploader
+                tags: vec![],
+                artist: None,
+                uploader: None,
+                source_url: non_empty(wallpaper.source),
+                post_url: Some(wallpaper.url),
+                created_at: NaiveDateTime::parse_from_str(
+                    &wallpaper.created_at,
+                    "%Y-%m-%d %H:%M:%S",
+                )
+                .ok()
+                .map(|created_at| Utc.from_utc_datetime(&created_at)),
+            })
+            .collect())
+    }
+}
+
+impl WallpaperSource for Wallhaven {
+    fn name(&self) -> &str {
+        "wallhaven"
+    }
+
+    // Categories, ratios, sorting and pages all end up in the search, the api key unlocks nsfw
+    fn cache_key(&self) -> String {
+        let config = serde_json::to_string(&self.config).unwrap_or_default();
+        format!("{} {config}", self.name())
+    }
+
+    fn fetch_wallpaper_list<'a>(
+        &'a self,
+        filters: &'a Filters,
+    ) -> BoxFuture<'a, Result<Vec<Post>, reqwest::Error>> {
+        self.fetch(filters).boxed()
+    }
+}
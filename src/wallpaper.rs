@@ -1,26 +1,19 @@
-use std::{
-    fs,
-    path::{Path, PathBuf},
-    process::Command,
-};
+use std::path::{Path, PathBuf};
 
 use crate::{
-    config::get_config,
+    config::Config,
+    crop::crop_for_output,
+    daemon,
+    library::Library,
+    output::Output,
     rating::{Category, Rating},
+    setter::Setter,
+    source::Post,
 };
-use anyhow::Result;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Hash, Clone, Ord, PartialOrd)]
-pub struct KonachanWallpaper {
-    pub md5: String,
-    pub file_url: String,
-    pub width: i32,
-    pub height: i32,
-    pub score: i32,
-    pub rating: Rating,
-}
-
 pub type DownloadedImagePath = PathBuf;
 pub type CroppedImagePath = PathBuf;
 
@@ -36,15 +29,11 @@ pub enum Prefered {
     Cropped,
 }
 
-#[derive(Debug, thiserror::Error)]
-pub enum SetWallpaperError {
-    #[error(transparent)]
-    IOError(#[from] std::io::Error),
-}
-
 #[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Hash, Clone, Ord, PartialOrd)]
 pub struct Wallpaper {
     pub md5: String,
+    pub source: String,
+    pub post_id: Option<String>,
     pub original_url: String,
     pub original_width: i32,
     pub original_height: i32,
@@ -54,75 +43,148 @@ pub struct Wallpaper {
     pub category: Option<Category>,
     pub downloaded_image_path: DownloadedImagePath,
     pub crop_data: Option<CropData>,
+    pub tags: Vec<String>,
+    pub artist: Option<String>,
+    pub uploader: Option<String>,
+    pub source_url: Option<String>,
+    pub post_url: Option<String>,
+    pub created_at: Option<DateTime<Utc>>,
+    pub added_at: Option<DateTime<Utc>>,
+    pub reviewed_at: Option<DateTime<Utc>>,
+    pub last_set_at: Option<DateTime<Utc>>,
 }
 
-pub fn set_wallpaper(
-    config_dir: &Path,
+pub async fn set_wallpaper(
+    config: &Config,
     path: &Path,
+    output: &str,
+    targeted: bool,
     set_wallpaper_command_override: Option<String>,
 ) -> Result<()> {
-    let command =
-        set_wallpaper_command_override.unwrap_or(get_config(&config_dir)?.set_wallpaper_command);
-
-    let (command_name, command_args) = command
-        .split_once(" ")
-        .expect("Invalid set wallpaper command, check your config");
+    // Setters and the daemon are waited for, which would otherwise hold up the runtime
+    let (config, path, output) = (config.clone(), path.to_owned(), output.to_owned());
+    tokio::task::spawn_blocking(move || {
+        // The daemon keeps track of the setters it started, so it is the one to replace them
+        if let Some(result) = daemon::set_wallpaper(
+            &path,
+            &output,
+            targeted,
+            set_wallpaper_command_override.clone(),
+        ) {
+            return result;
+        }
 
-    Command::new(command_name)
-        .args(
-            command_args
-                .replace("{}", &path.to_string_lossy())
-                .split(" "),
-        )
-        .spawn()?;
-    Ok(())
+        let (setter, command) = match set_wallpaper_command_override {
+            Some(command) => (Setter::Command, command),
+            None => (config.setter, config.set_wallpaper_command),
+        };
+        setter.set(
+            &command,
+            &path,
+            &output,
+            targeted,
+            config.setter_output_args.get(&output),
+        )?;
+        Ok(())
+    })
+    .await?
 }
 
 impl Wallpaper {
-    pub fn from_konachan(
-        wallpaper: KonachanWallpaper,
+    pub fn from_post(
+        post: Post,
+        md5: String,
         downloaded_image_path: DownloadedImagePath,
     ) -> Wallpaper {
         Wallpaper {
-            md5: wallpaper.md5,
-            original_url: wallpaper.file_url,
-            original_width: wallpaper.width,
-            original_height: wallpaper.height,
-            score: wallpaper.score,
-            rating: wallpaper.rating,
+            md5,
+            source: post.source,
+            post_id: Some(post.id),
+            original_url: post.file_url,
+            original_width: post.width,
+            original_height: post.height,
+            score: post.score,
+            rating: post.rating,
             category: None,
             prefered: Prefered::Original,
             downloaded_image_path,
             crop_data: None,
+            tags: post.tags,
+            artist: post.artist,
+            uploader: post.uploader,
+            source_url: post.source_url,
+            post_url: post.post_url,
+            created_at: post.created_at,
+            added_at: Some(Utc::now()),
+            reviewed_at: None,
+            last_set_at: None,
         }
     }
 
-    pub fn from_md5(wallpapers_dir: &Path, md5: &String) -> Result<Self> {
-        let path = wallpapers_dir.join(md5).with_extension("json");
+    pub fn from_local(
+        md5: String,
+        original_path: &Path,
+        width: i32,
+        height: i32,
+        rating: Rating,
+        downloaded_image_path: DownloadedImagePath,
+    ) -> Wallpaper {
+        Wallpaper {
+            md5,
+            source: "local".to_owned(),
+            post_id: None,
+            original_url: original_path.to_string_lossy().into(),
+            original_width: width,
+            original_height: height,
+            score: 0,
+            rating,
+            category: None,
+            prefered: Prefered::Original,
+            downloaded_image_path,
+            crop_data: None,
+            tags: vec![],
+            artist: None,
+            uploader: None,
+            source_url: None,
+            post_url: None,
+            created_at: None,
+            added_at: Some(Utc::now()),
+            reviewed_at: None,
+            last_set_at: None,
+        }
+    }
 
-        let content = fs::read_to_string(path)?;
-        let wallpaper = serde_json::from_str(&content)?;
-        Ok(wallpaper)
+    pub fn from_md5(library: &Library, md5: &String) -> Result<Self> {
+        library
+            .get_wallpaper(md5)?
+            .with_context(|| format!("Wallpaper {md5} is not in the library"))
     }
-    pub fn set_prefered(
+    pub async fn set_prefered(
         &self,
-        config_dir: &Path,
+        config: &Config,
+        output: &Output,
         set_wallpaper_command_override: Option<String>,
     ) -> Result<Prefered> {
         match (&self.prefered, &self.crop_data) {
             (Prefered::Cropped, Some(crop_data)) => {
                 set_wallpaper(
-                    config_dir,
-                    &crop_data.cropped_image_path,
+                    config,
+                    &crop_for_output(self, crop_data, output).await?,
+                    &output.name,
+                    output.targeted,
                     set_wallpaper_command_override,
-                )?;
+                )
+                .await?;
             }
             (Prefered::Original, _) | (Prefered::Cropped, None) => {
                 set_wallpaper(
-                    config_dir,
+                    config,
                     &self.downloaded_image_path,
+                    &output.name,
+                    output.targeted,
                     set_wallpaper_command_override,
-                )?;
+                )
+                .await?;
             }
         };
         Ok(self.prefered.to_owned())
@@ -1,36 +1,9 @@
-use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use std::{
     collections::VecDeque,
-    fs,
-    io::Read,
     ops::{Deref, DerefMut},
-    path::Path,
 };
 
-pub fn get_history(wallpapers_dir: &Path) -> Result<History> {
-    let path = wallpapers_dir.join("history");
-    let mut file = fs::OpenOptions::new()
-        .read(true)
-        .write(true)
-        .create(true)
-        .open(path)?;
-
-    let mut contents = String::new();
-    file.read_to_string(&mut contents)?;
-    if let Ok(history) = serde_json::from_str(&contents) {
-        Ok(history)
-    } else {
-        Ok(History::new())
-    }
-}
-pub fn save_history(wallpapers_dir: &Path, history: &History) -> Result<()> {
-    if let Ok(json) = serde_json::to_string(&history) {
-        fs::write(wallpapers_dir.join("history").as_path(), json.as_bytes())?
-    }
-    Ok(())
-}
-
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct History {
     arr: VecDeque<String>,
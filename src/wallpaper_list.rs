@@ -1,96 +1,111 @@
-use crate::cli::Range;
+use crate::cli::{Range, RatioFilter};
+use crate::order::Order;
 use crate::rating::KonachanRatingFilter;
-use crate::wallpaper::KonachanWallpaper;
+use crate::source::{Post, WallpaperSource};
 use anyhow::Result;
 use chrono::Duration;
 use std::path::Path;
 use tokio::fs::{self, File};
 
-async fn fetch_wallpaper_list(
-    width: &Range,
-    height: &Range,
-    tags: &Option<String>,
-    rating: &KonachanRatingFilter,
-) -> Result<Vec<KonachanWallpaper>, reqwest::Error> {
-    let mut page = 0;
-    let limit = 1000;
-    let mut wallpapers: Vec<KonachanWallpaper> = vec![];
-    let tags = tags
-        .clone()
-        .map_or("".to_string(), |s| s.replace(' ', "%20"));
-
-    loop {
-        page += 1;
-        let mut response = reqwest::get(format!(
-            "https://konachan.net/post.json?limit={limit}&tags={tags}%20rating%3A{rating}%20width%3A{width}+height%3A{height}&page={page}"
-        ))
-        .await?
-        .json::<Vec<KonachanWallpaper>>()
-        .await?;
+#[derive(Debug, Clone)]
+pub struct Filters {
+    pub width: Range,
+    pub height: Range,
+    pub tags: Option<String>,
+    pub rating: KonachanRatingFilter,
+    pub order: Order,
+    pub ratio: Option<RatioFilter>,
+}
 
-        wallpapers.append(&mut response);
+impl Filters {
+    pub fn tag_query(&self) -> String {
+        let Filters {
+            width,
+            height,
+            tags,
+            rating,
+            ..
+        } = self;
+        let tags = tags.clone().unwrap_or("".to_string());
+        let width = width.relaxed();
+        let height = height.relaxed();
+        format!("{tags} rating:{rating} width:{width} height:{height}")
+            .trim()
+            .to_owned()
+    }
 
-        if response.len() < limit {
-            break;
+    // Danbooru and gelbooru take the exact dimensions, only the ratings are spelled their own way.
+    // Without `dimensions` they are left to `matches`
+    pub fn booru_tag_query(&self, rating: &str, dimensions: bool) -> String {
+        let tags = self.tags.clone().unwrap_or("".to_string());
+        let mut query = format!("{tags} {rating}");
+        if dimensions {
+            query += &format!(
+                " width:{} height:{}",
+                self.width.comparison(),
+                self.height.comparison()
+            );
         }
+        query.trim().to_owned()
     }
-    // wallpapers.sort_by(|a, b| (a.width / a.height).cmp(&(b.width / b.height)));
-    wallpapers.sort_by(|a, b| a.score.cmp(&b.score));
 
-    Ok(wallpapers)
+    // Whatever the source couldn't search for
+    pub fn matches(&self, post: &Post) -> bool {
+        self.width.contains(post.width)
+            && self.height.contains(post.height)
+            && self
+                .ratio
+                .as_ref()
+                .is_none_or(|ratio| ratio.contains(post.width, post.height))
+    }
 }
 
-fn compute_hash_for_filters(
-    width: &Range,
-    height: &Range,
-    tags: &Option<String>,
-    rating: &KonachanRatingFilter,
-) -> String {
+fn compute_hash_for_filters(source: &dyn WallpaperSource, filters: &Filters) -> String {
+    let Filters {
+        width,
+        height,
+        tags,
+        rating,
+        order,
+        ..
+    } = filters;
     let tags = tags.clone().unwrap_or("".to_string());
-    let digest = md5::compute(format!("{tags}{rating}{width}{height}").as_bytes());
+    let width = width.relaxed();
+    let height = height.relaxed();
+    let source = source.cache_key();
+    let digest = md5::compute(format!("{source}{tags}{rating}{width}{height}{order}").as_bytes());
     format!("{:x}", digest)
 }
-async fn get_cached_wallpaper_list(
-    cache_dir: &Path,
-    width: &Range,
-    height: &Range,
-    tags: &Option<String>,
-    rating: &KonachanRatingFilter,
-) -> Result<Vec<KonachanWallpaper>> {
-    let content =
-        fs::read_to_string(cache_dir.join(compute_hash_for_filters(width, height, tags, rating)))
-            .await?;
+async fn get_cached_wallpaper_list(cache_dir: &Path, filters_hashed: &str) -> Result<Vec<Post>> {
+    let content = fs::read_to_string(cache_dir.join(filters_hashed)).await?;
     Ok(serde_json::from_str(&content)?)
 }
 
+async fn get_cache_age(cache_dir: &Path, filters_hashed: &str) -> Result<i64> {
+    let elapsed = File::open(cache_dir.join(filters_hashed))
+        .await?
+        .metadata()
+        .await?
+        .modified()?
+        .elapsed()?;
+    Ok(Duration::from_std(elapsed).map(|dur| dur.num_days())?)
+}
+
 pub async fn get_wallpaper_list(
     cache_dir: &Path,
-    width: &Range,
-    height: &Range,
-    tags: &Option<String>,
-    rating: &KonachanRatingFilter,
-) -> Result<Vec<KonachanWallpaper>> {
-    let cached_wallpaper_list: Result<Vec<KonachanWallpaper>> =
-        get_cached_wallpaper_list(cache_dir, width, height, tags, rating).await;
-    let filters_hashed = compute_hash_for_filters(width, height, tags, rating);
+    source: &dyn WallpaperSource,
+    filters: &Filters,
+) -> Result<Vec<Post>> {
+    let filters_hashed = compute_hash_for_filters(source, filters);
+    let cached_wallpaper_list: Result<Vec<Post>> =
+        get_cached_wallpaper_list(cache_dir, &filters_hashed).await;
+    let cache_age: Result<i64> = get_cache_age(cache_dir, &filters_hashed).await;
 
-    let cache_age: Result<i64> = try {
-        Duration::from_std(
-            File::open(cache_dir.join(&filters_hashed))
-                .await?
-                .metadata()
-                .await?
-                .modified()?
-                .elapsed()?,
-        )
-        .map(|dur| dur.num_days())?
-    };
-
-    let wallpapers: Vec<KonachanWallpaper> = match (cached_wallpaper_list, cache_age) {
+    let mut wallpapers: Vec<Post> = match (cached_wallpaper_list, cache_age) {
         (Ok(cached), Ok(..=5)) if !cached.is_empty() => cached,
         (cached, _) => {
-            let fetched_wallpaper_list: Result<Vec<KonachanWallpaper>, reqwest::Error> =
-                fetch_wallpaper_list(width, height, tags, rating).await;
+            let fetched_wallpaper_list: Result<Vec<Post>, reqwest::Error> =
+                source.fetch_wallpaper_list(filters).await;
 
             match fetched_wallpaper_list {
                 Ok(fetched) => {
@@ -104,5 +119,7 @@ pub async fn get_wallpaper_list(
             }
         }
     };
+    wallpapers.retain(|post| filters.matches(post));
+
     Ok(wallpapers)
 }